@@ -0,0 +1,144 @@
+//!
+//! Persistent Linked List for clonables, backed by [Arc] instead of `Rc`.
+//! This is the `Send + Sync` counterpart to [LinkList](super::link_list::LinkList):
+//! use it when a list needs to cross a thread boundary (e.g. parallel
+//! permutation processing); otherwise prefer `LinkList`, since `Arc`'s
+//! atomic reference counting is slower than `Rc`'s when a list never
+//! leaves a single thread.
+//!
+use std::{borrow::Borrow, fmt::Debug, sync::Arc};
+
+// A link in a linked list.
+// If the wrapped Option is a Some then
+// then the link leads to the next node in the list.
+// Otherwise, when the wrapped option is a None,
+// the the link is a terminal link.
+type Link<T> = Arc<Option<LinkNode<T>>>;
+
+// a node in a linked list
+#[derive(Debug, Clone, PartialEq)]
+struct LinkNode<T> {
+    elem: T,
+    tail: Link<T>,
+}
+
+impl <T> LinkNode<T> {
+    // construct a terminal node
+    fn null() -> Arc<Option<T>> {
+        Arc::new(None)
+    }
+}
+
+// A linked list.
+// This structure wraps the head node
+// and the length of the list.
+// This allows us to return the
+// length of the list in constant time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcLinkList<T> {
+    size: usize,
+    list: Link<T>,
+}
+
+impl <T> ArcLinkList<T> where T: Clone + Debug + PartialEq {
+    ///
+    /// Create a new empty list
+    ///
+    pub fn new() -> ArcLinkList<T> {
+        ArcLinkList::<T>{size: 0, list: LinkNode::null()}
+    }
+
+    pub fn of_one(elem: T) -> ArcLinkList<T> {
+        ArcLinkList::new().insert(elem)
+    }
+
+    ///
+    /// Determine if the list is empty
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.list.is_none()
+    }
+
+    ///
+    /// Number of nodes in the list
+    ///
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    ///
+    /// Get the element at the head of the list
+    ///
+    pub fn head(&self) -> Option<T> {
+        self.list.as_ref().as_ref().map(|node| node.elem.clone())
+    }
+
+    ///
+    /// Get the list's tail (the list after the head element)
+    /// - The empty list has no tail, so this returns an option
+    ///
+    pub fn tail(&self) -> Option<ArcLinkList<T>> {
+        match self.list.as_ref() {
+            Some(node) => {
+                match &node.tail.borrow() {
+                    Some(_) => Some(ArcLinkList{size: self.size - 1, list: node.tail.clone()}),
+                    None => Some(ArcLinkList::new()),  // empty list
+                }
+            },
+            None => None,
+        }
+    }
+
+    ///
+    /// Insert an element at the head of the list
+    ///
+    pub fn insert(&self, elem: T) -> ArcLinkList<T> {
+        match self.list.as_ref() {
+            Some(_) => {
+                ArcLinkList{size: self.size + 1, list: Arc::new(Some(LinkNode{elem, tail: self.list.clone()}))}
+            },
+            None => {
+                ArcLinkList{size: 1, list: Arc::new(Some(LinkNode{elem, tail: LinkNode::null()}))}
+            },
+        }
+    }
+}
+
+impl <T> Default for ArcLinkList<T> where T: Clone + Debug + PartialEq {
+    fn default() -> Self {
+        ArcLinkList::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_new_list_is_empty() {
+        let list = ArcLinkList::<i32>::new();
+        assert!(list.is_empty());
+        assert_eq!(0, list.size());
+    }
+
+    #[test]
+    fn test_insert_head_and_tail() {
+        let list = ArcLinkList::new().insert(3).insert(2).insert(1);
+        assert_eq!(3, list.size());
+        assert_eq!(Some(1), list.head());
+        assert_eq!(Some(2), list.tail().unwrap().head());
+    }
+
+    #[test]
+    fn test_arc_link_list_moves_into_and_reads_from_spawned_thread() {
+        let list = ArcLinkList::new().insert(3).insert(2).insert(1);
+
+        let handle = thread::spawn(move || {
+            assert_eq!(Some(1), list.head());
+            list.tail().unwrap().size()
+        });
+
+        assert_eq!(2, handle.join().unwrap());
+    }
+}
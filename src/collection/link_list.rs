@@ -73,6 +73,26 @@ impl <T> LinkList<T> where T: Clone + Debug + PartialEq {
         LinkList::new().insert(elem8).insert(elem7).insert(elem6).insert(elem5).insert(elem4).insert(elem3).insert(elem2).insert(elem)
     }
 
+    ///
+    /// Build a list by repeatedly applying `f` to a seed value: `f(seed)`
+    /// produces the next element and the next seed, or `None` to stop.
+    /// Elements appear in the order they were generated.
+    ///
+    /// Builds by inserting (prepending) each generated element and reversing
+    /// once at the end, the same `O(n)` approach `extend` uses, rather than
+    /// reversing after every element.
+    ///
+    pub fn unfold<S>(seed: S, f: fn(S) -> Option<(T, S)>) -> LinkList<T>
+    where S: Clone {
+        let mut list = LinkList::new();
+        let mut seed = seed;
+        while let Some((elem, next_seed)) = f(seed) {
+            list = list.insert(elem);
+            seed = next_seed;
+        }
+        list.reverse()
+    }
+
     ///
     /// Determine if the list is empty
     ///
@@ -175,6 +195,35 @@ impl <T> LinkList<T> where T: Clone + Debug + PartialEq {
         self.reverse().insert(elem).reverse()
     }
 
+    ///
+    /// Append every item from `items`, in order, to the end of the list.
+    ///
+    /// Appending elements one at a time via repeated `append` is O(n^2),
+    /// since each `append` reverses the list twice. This reverses once,
+    /// inserts all the new items, and reverses once at the end, so
+    /// extending by `m` items is O(n + m).
+    ///
+    pub fn extend(&self, items: impl IntoIterator<Item = T>) -> LinkList<T> {
+        let mut list = self.reverse();
+        for item in items {
+            list = list.insert(item);
+        }
+        list.reverse()
+    }
+
+    ///
+    /// Materialize the list into a `Vec<T>`, head first.
+    ///
+    pub fn to_vec(&self) -> Vec<T> {
+        let mut items = Vec::with_capacity(self.size());
+        let mut list = self.clone();
+        while !list.is_empty() {
+            items.push(list.head().unwrap());
+            list = list.tail().unwrap();
+        }
+        items
+    }
+
     ///
     /// Reverse the list
     ///
@@ -450,7 +499,7 @@ impl <T> LinkList<T> where T: Clone + Debug + PartialEq {
      * @param <R> the result type
      * @return list of elements mapped from T to R
      */
-    pub fn map<R>(&self, mapper: fn(&T) -> R) -> LinkList<R>
+    pub fn map<R>(&self, mut mapper: impl FnMut(&T) -> R) -> LinkList<R>
         where R: Clone + Debug + PartialEq
     {
         if self.is_empty() {
@@ -474,13 +523,66 @@ impl <T> LinkList<T> where T: Clone + Debug + PartialEq {
         mapped_list.reverse() // un-reverse it.
     }
 
+    ///
+    /// Fold the list into a running sequence of accumulators: unlike a plain
+    /// fold (which only returns the final accumulator), this keeps every
+    /// intermediate value, with `init` itself as the first element, so
+    /// `[1,2,3].prefix_fold(1, |a,x| a*x)` yields `[1,1,2,6]`. An empty list
+    /// yields a single-element list containing just `init`.
+    ///
+    pub fn prefix_fold<B>(&self, init: B, f: fn(&B, &T) -> B) -> LinkList<B>
+        where B: Clone + Debug + PartialEq
+    {
+        let mut prefixes = LinkList::<B>::new().insert(init.clone());
+        let mut accumulator = init;
+        let mut list = self.clone();
+        while !list.is_empty() {
+            let head = list.head().unwrap();
+            accumulator = f(&accumulator, &head);
+            prefixes = prefixes.insert(accumulator.clone());
+            list = list.tail().unwrap();
+        }
+        prefixes.reverse()
+    }
+
+    ///
+    /// Fold the list head-to-tail into a single accumulated value, starting
+    /// from `init`; unlike [LinkList::prefix_fold], only the final
+    /// accumulator is kept. Iterative, so it doesn't grow the stack on a
+    /// long list.
+    ///
+    pub fn fold<B>(&self, init: B, f: fn(B, &T) -> B) -> B {
+        let mut accumulator = init;
+        let mut list = self.clone();
+        while !list.is_empty() {
+            accumulator = f(accumulator, &list.head().unwrap());
+            list = list.tail().unwrap();
+        }
+        accumulator
+    }
+
+    ///
+    /// [LinkList::fold] seeded with this list's first element, rather than
+    /// a caller-supplied `init`. `None` for an empty list, since there's no
+    /// element to seed with.
+    ///
+    pub fn reduce(&self, f: fn(&T, &T) -> T) -> Option<T> {
+        let mut accumulator = self.head()?;
+        let mut list = self.tail().unwrap();
+        while !list.is_empty() {
+            accumulator = f(&accumulator, &list.head().unwrap());
+            list = list.tail().unwrap();
+        }
+        Some(accumulator)
+    }
+
     /**
      * Filter a list given a predicate.
      *
      * @param predicate
      * @return a new list with those elements where predicate.test() returns true.
      */
-    pub fn filter(&self, predicate: fn(&T) -> bool) -> LinkList<T>
+    pub fn filter(&self, mut predicate: impl FnMut(&T) -> bool) -> LinkList<T>
     {
         if self.is_empty() {
             return self.clone();
@@ -503,6 +605,53 @@ impl <T> LinkList<T> where T: Clone + Debug + PartialEq {
         return_list.reverse()
     }
 
+    ///
+    /// Filter a list given a predicate, like [LinkList::filter], but also
+    /// report how many elements were removed (failed the predicate) — handy
+    /// for reporting things like "removed N invalid entries".
+    ///
+    pub fn filter_counted(&self, predicate: fn(&T) -> bool) -> (LinkList<T>, usize) {
+        if self.is_empty() {
+            return (self.clone(), 0);
+        }
+
+        let mut removed_count: usize = 0;
+        let mut return_list = LinkList::new();
+        let mut list = self.clone();
+        while !list.is_empty() {
+            let head = list.head().unwrap();
+            if predicate(&head) {
+                return_list = return_list.insert(head);
+            } else {
+                removed_count += 1;
+            }
+            list = list.tail().unwrap();
+        }
+        (return_list.reverse(), removed_count)
+    }
+
+    ///
+    /// Split this list at the first element that fails `predicate`: the
+    /// longest prefix satisfying `predicate`, and the remaining suffix
+    /// (starting with the first element that failed it, or empty if every
+    /// element satisfied it). Equivalent to `(take_while(p), drop_while(p))`,
+    /// but walks the list once instead of twice.
+    ///
+    pub fn span(&self, predicate: fn(&T) -> bool) -> (LinkList<T>, LinkList<T>) {
+        let mut prefix = LinkList::new();
+        let mut list = self.clone();
+        while !list.is_empty() {
+            let head = list.head().unwrap();
+            if predicate(&head) {
+                prefix = prefix.insert(head);
+                list = list.tail().unwrap();
+            } else {
+                break;
+            }
+        }
+        (prefix.reverse(), list)
+    }
+
     //
     // convert LinkList to LinkList of LinkList.
     // (this is the inverse of flatten)
@@ -524,15 +673,36 @@ impl <T> LinkList<LinkList<T>> where T: Clone + Debug + PartialEq {
     // convert a LinkList of LinkList into a LinkList
     //
     pub fn flatten(&self) -> LinkList<T> {
+        self.concat_all()
+    }
+
+    ///
+    /// Flatten a list of lists into a single list, in order.
+    ///
+    /// `flatten` used to fold the sublists together with repeated calls to
+    /// `concat`, each of which reverses both lists, giving quadratic
+    /// behavior overall for many sublists. This collects every element into
+    /// a `Vec` in a single pass and rebuilds one list from it instead.
+    ///
+    pub fn concat_all(&self) -> LinkList<T> {
         if self.is_empty() {
             return LinkList::new()
         }
-        let mut return_list = LinkList::<T>::new();
+        let mut elements: Vec<T> = Vec::new();
         let mut list = self.clone();
         while !list.is_empty() {
-            return_list = return_list.concat(&list.head().unwrap());
+            let mut sublist = list.head().unwrap();
+            while !sublist.is_empty() {
+                elements.push(sublist.head().unwrap());
+                sublist = sublist.tail().unwrap();
+            }
             list = list.tail().unwrap();
         }
+
+        let mut return_list = LinkList::<T>::new();
+        for elem in elements.into_iter().rev() {
+            return_list = return_list.insert(elem);
+        }
         return_list
     }
 
@@ -633,6 +803,111 @@ impl <T> LinkList<LinkList<T>> where T: Clone + Debug + PartialEq {
 
 }
 
+///
+/// Lexicographic ordering of lists: compare element by element,
+/// and when one list is a prefix of the other, the shorter list orders first.
+///
+impl <T> PartialOrd for LinkList<T> where T: Clone + Debug + PartialEq + PartialOrd {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        let mut left = self.clone();
+        let mut right = other.clone();
+        loop {
+            match (left.head(), right.head()) {
+                (None, None) => return Some(std::cmp::Ordering::Equal),
+                (None, Some(_)) => return Some(std::cmp::Ordering::Less),
+                (Some(_), None) => return Some(std::cmp::Ordering::Greater),
+                (Some(left_head), Some(right_head)) => {
+                    match left_head.partial_cmp(&right_head) {
+                        Some(std::cmp::Ordering::Equal) => {
+                            left = left.tail().unwrap();
+                            right = right.tail().unwrap();
+                        },
+                        other => return other,
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl <T> Eq for LinkList<T> where T: Clone + Debug + PartialEq + Eq {}
+
+impl <T> Ord for LinkList<T> where T: Clone + Debug + PartialEq + Ord {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.partial_cmp(other).expect("Ord requires a total order")
+    }
+}
+
+impl <T> LinkList<T> where T: Clone + Debug + PartialEq + std::fmt::Display {
+    ///
+    /// Render the list as a `sep`-joined string, e.g. `["a", "b", "c"].join(" + ")`
+    /// is `"a + b + c"`. The empty list renders as `""`, with no trailing separator.
+    ///
+    pub fn join(&self, sep: &str) -> String {
+        let mut rendered = String::new();
+        let mut list = self.clone();
+        let mut first = true;
+        while !list.is_empty() {
+            if !first {
+                rendered.push_str(sep);
+            }
+            rendered.push_str(&list.head().unwrap().to_string());
+            first = false;
+            list = list.tail().unwrap();
+        }
+        rendered
+    }
+}
+
+///
+/// An iterator over a [LinkList], yielding a clone of each element from
+/// head to tail. Holds the remaining suffix of the list and advances by
+/// calling [LinkList::tail] on each [Iterator::next].
+///
+pub struct LinkListIter<T> where T: Clone + Debug + PartialEq {
+    list: LinkList<T>,
+}
+
+impl <T> Iterator for LinkListIter<T> where T: Clone + Debug + PartialEq {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let head = self.list.head();
+        if head.is_some() {
+            self.list = self.list.tail().unwrap();
+        }
+        head
+    }
+}
+
+impl <T> IntoIterator for LinkList<T> where T: Clone + Debug + PartialEq {
+    type Item = T;
+    type IntoIter = LinkListIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        LinkListIter { list: self }
+    }
+}
+
+impl <T> IntoIterator for &LinkList<T> where T: Clone + Debug + PartialEq {
+    type Item = T;
+    type IntoIter = LinkListIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        LinkListIter { list: self.clone() }
+    }
+}
+
+///
+/// Build a [LinkList] from any `T`-yielding iterator, preserving iteration
+/// order, by [LinkList::extend]ing an empty list.
+///
+impl <T> std::iter::FromIterator<T> for LinkList<T> where T: Clone + Debug + PartialEq {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> LinkList<T> {
+        LinkList::new().extend(iter)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -863,6 +1138,37 @@ mod tests {
         assert!(list.is_empty());
     }
 
+    #[test]
+    fn test_extend() {
+        let list = LinkList::<i32>::new().append(1).append(2);
+        let extended = list.extend(vec![3, 4, 5]);
+        assert_eq!(extended, LinkList::<i32>::new().append(1).append(2).append(3).append(4).append(5));
+
+        //
+        // extending an empty list with items is the items, in order
+        //
+        let extended = LinkList::<i32>::new().extend(vec![1, 2, 3]);
+        assert_eq!(extended, LinkList::<i32>::new().append(1).append(2).append(3));
+
+        //
+        // extending with an empty iterator leaves the list unchanged
+        //
+        let extended = list.extend(Vec::<i32>::new());
+        assert_eq!(extended, list);
+    }
+
+    #[test]
+    fn test_unfold_counts_up_in_generation_order() {
+        let list = LinkList::unfold(1, |n| if n <= 5 { Some((n, n + 1)) } else { None });
+        assert_eq!(list, LinkList::<i32>::new().append(1).append(2).append(3).append(4).append(5));
+    }
+
+    #[test]
+    fn test_unfold_immediately_none_seed_yields_empty_list() {
+        let list = LinkList::unfold(1, |_n| None::<(i32, i32)>);
+        assert!(list.is_empty());
+    }
+
     #[test]
     fn test_remove_at() {
         let list = LinkList::<i32>::new().append(1).append(2).append(3);
@@ -985,6 +1291,68 @@ mod tests {
         assert_eq!(LinkList::<String>::new(), mapped_list);
     }
 
+    #[test]
+    fn test_map_with_closure_capturing_a_local_variable() {
+        let list = LinkList::<i32>::new().append(1).append(2).append(3); // 1,2,3
+        let factor = 3;
+
+        let mapped_list = list.map::<i32>(|x| x * factor);
+
+        assert_eq!(LinkList::<i32>::new().append(3).append(6).append(9), mapped_list);
+    }
+
+    #[test]
+    fn test_prefix_fold_accumulates_running_product() {
+        let list = LinkList::<i32>::new().append(1).append(2).append(3);
+
+        let prefixes = list.prefix_fold::<i32>(1, |a, x| a * x);
+
+        assert_eq!(LinkList::<i32>::new().append(1).append(1).append(2).append(6), prefixes);
+    }
+
+    #[test]
+    fn test_prefix_fold_on_empty_list_yields_just_init() {
+        let list = LinkList::<i32>::new();
+
+        let prefixes = list.prefix_fold::<i32>(1, |a, x| a * x);
+
+        assert_eq!(LinkList::<i32>::new().append(1), prefixes);
+    }
+
+    #[test]
+    fn test_fold_sums_list() {
+        let list = LinkList::<i32>::new().append(1).append(2).append(3).append(4);
+
+        let sum = list.fold(0, |a, x| a + x);
+
+        assert_eq!(10, sum);
+    }
+
+    #[test]
+    fn test_fold_on_empty_list_yields_just_init() {
+        let list = LinkList::<i32>::new();
+
+        let sum = list.fold(0, |a, x| a + x);
+
+        assert_eq!(0, sum);
+    }
+
+    #[test]
+    fn test_reduce_sums_list() {
+        let list = LinkList::<i32>::new().append(1).append(2).append(3).append(4);
+
+        let sum = list.reduce(|a, x| a + x);
+
+        assert_eq!(Some(10), sum);
+    }
+
+    #[test]
+    fn test_reduce_on_empty_list_yields_none() {
+        let list = LinkList::<i32>::new();
+
+        assert_eq!(None, list.reduce(|a, x| a + x));
+    }
+
     #[test]
     fn test_filter() {
         let list = LinkList::<i32>::new().append(1).append(2).append(3);
@@ -1006,6 +1374,49 @@ mod tests {
         assert!(filtered.is_empty());
     }
 
+    #[test]
+    fn test_filter_with_closure_capturing_a_local_variable() {
+        let list = LinkList::<i32>::new().append(1).append(2).append(3).append(4);
+        let threshold = 2;
+
+        let filtered = list.filter(|i| *i > threshold);
+
+        assert_eq!(LinkList::<i32>::new().append(3).append(4), filtered);
+    }
+
+    #[test]
+    fn test_filter_counted() {
+        let list = LinkList::<i32>::new().append(1).append(2).append(3).append(4);
+
+        let (filtered, removed_count) = list.filter_counted(|i| 0 == i % 2);
+        assert_eq!(LinkList::<i32>::new().append(2).append(4), filtered);
+        assert_eq!(2, removed_count);
+
+        // empty list yields empty list and zero removed
+        let list = LinkList::<i32>::new();
+        let (filtered, removed_count) = list.filter_counted(|i| 0 == i % 2);
+        assert!(filtered.is_empty());
+        assert_eq!(0, removed_count);
+    }
+
+    #[test]
+    fn test_span_splits_on_first_predicate_failure() {
+        let list = LinkList::<i32>::new().append(2).append(4).append(5).append(6);
+
+        let (prefix, suffix) = list.span(|i| 0 == i % 2);
+        assert_eq!(LinkList::<i32>::new().append(2).append(4), prefix);
+        assert_eq!(LinkList::<i32>::new().append(5).append(6), suffix);
+    }
+
+    #[test]
+    fn test_span_all_satisfying_yields_whole_list_and_empty_suffix() {
+        let list = LinkList::<i32>::new().append(2).append(4).append(6);
+
+        let (prefix, suffix) = list.span(|i| 0 == i % 2);
+        assert_eq!(list, prefix);
+        assert!(suffix.is_empty());
+    }
+
     #[test]
     fn test_fatten() {
         let list = LinkList::<i32>::new().append(1).append(2).append(3);
@@ -1036,6 +1447,24 @@ mod tests {
         assert_eq!(LinkList::<i32>::new(), LinkList::<LinkList<i32>>::new().flatten());
     }
 
+    #[test]
+    fn test_concat_all_many_singleton_sublists() {
+        let mut list = LinkList::<LinkList<i32>>::new();
+        for i in 0..100 {
+            list = list.append(LinkList::<i32>::new().insert(i));
+        }
+
+        let concatenated = list.concat_all();
+
+        let mut expected = LinkList::<i32>::new();
+        for i in (0..100).rev() {
+            expected = expected.insert(i);
+        }
+        assert_eq!(100, concatenated.size());
+        assert_eq!(expected, concatenated);
+        assert_eq!(list.flatten(), concatenated);
+    }
+
     #[test]
     fn test_flatmap() {
         let list = LinkList::<LinkList<i32>>::new()
@@ -1115,4 +1544,74 @@ mod tests {
         }
 
     }
+
+    #[test]
+    fn test_partial_ord_lexicographic() {
+        let one_two = LinkList::<i32>::of_two(1, 2);
+        let one_three = LinkList::<i32>::of_two(1, 3);
+        assert!(one_two < one_three);
+
+        let one = LinkList::<i32>::of_one(1);
+        assert!(one < one_two);
+
+        let one_two_again = LinkList::<i32>::of_two(1, 2);
+        assert_eq!(std::cmp::Ordering::Equal, one_two.cmp(&one_two_again));
+    }
+
+    #[test]
+    fn test_into_iter_collects_elements_in_order() {
+        let list = LinkList::<i32>::of_three(1, 2, 3);
+        let collected: Vec<i32> = list.into_iter().collect();
+        assert_eq!(vec!(1, 2, 3), collected);
+    }
+
+    #[test]
+    fn test_into_iter_by_reference_leaves_list_usable() {
+        let list = LinkList::<i32>::of_three(1, 2, 3);
+        let collected: Vec<i32> = (&list).into_iter().collect();
+        assert_eq!(vec!(1, 2, 3), collected);
+        assert_eq!(3, list.size()); // list was only borrowed, so it's still usable
+    }
+
+    #[test]
+    fn test_into_iter_for_loop() {
+        let list = LinkList::<i32>::of_three(1, 2, 3);
+        let mut sum = 0;
+        for item in &list {
+            sum += item;
+        }
+        assert_eq!(6, sum);
+    }
+
+    #[test]
+    fn test_into_iter_of_empty_list_yields_zero_items() {
+        let list = LinkList::<i32>::new();
+        let collected: Vec<i32> = list.into_iter().collect();
+        assert!(collected.is_empty());
+    }
+
+    #[test]
+    fn test_from_iterator_to_vec_round_trips() {
+        let list: LinkList<i32> = vec!(1, 2, 3).into_iter().collect();
+        assert_eq!(vec!(1, 2, 3), list.to_vec());
+    }
+
+    #[test]
+    fn test_from_iterator_of_empty_iterator_is_empty_list() {
+        let list: LinkList<i32> = Vec::<i32>::new().into_iter().collect();
+        assert!(list.is_empty());
+        assert!(list.to_vec().is_empty());
+    }
+
+    #[test]
+    fn test_join_separates_elements_with_separator() {
+        let list: LinkList<String> = vec!("a".to_string(), "b".to_string(), "c".to_string()).into_iter().collect();
+        assert_eq!("a + b + c".to_string(), list.join(" + "));
+    }
+
+    #[test]
+    fn test_join_of_empty_list_is_empty_string() {
+        let list = LinkList::<String>::new();
+        assert_eq!("".to_string(), list.join(" + "));
+    }
 }
@@ -1 +1,2 @@
 pub mod link_list;
+pub mod arc_link_list;
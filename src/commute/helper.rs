@@ -0,0 +1,246 @@
+//!
+//! Helpers for reasoning about expressions up to reordering of their
+//! commutative (`+`/`*`) operands: generating every commuted form of an
+//! expression, and checking whether two expressions are equivalent under
+//! that reordering. See [crate::expression::node::ExpressionNode::equivalent]
+//! for a cheaper (but less exhaustive) equivalence check that sorts operands
+//! into a canonical order instead of enumerating every commuted form.
+//!
+use crate::collection::link_list::LinkList;
+use crate::expression::error::ParsingError;
+use crate::expression::node::ExpressionNode;
+use crate::expression::parse::{parse_expression, ParseOptions};
+use crate::expression::position::ParsePosition;
+use crate::expression::value::SignType;
+use crate::scan::context::beginning;
+
+///
+/// Every distinct expression reachable from `node` by reordering the
+/// operands of its `Sum`/`Product` nodes, recursively (see
+/// [count_commuted_forms](crate::expression::node::count_commuted_forms) for
+/// a count of these without materializing them; this grows just as fast,
+/// so only call it on modestly-sized expressions). Positions in the
+/// returned nodes are not meaningful, since a commuted form doesn't
+/// correspond to any single span of the original source, so they're
+/// normalized to [ParsePosition::default].
+///
+pub fn generate_commuted_expressions(node: &ExpressionNode) -> LinkList<ExpressionNode> {
+    commuted_forms(&normalize_positions(node)).into_iter().collect()
+}
+
+///
+/// Parse `left` and `right`, then check whether `right` is one of `left`'s
+/// commuted forms (see [generate_commuted_expressions]), using
+/// [LinkList::find] for the membership check.
+///
+pub fn are_expressions_equivalent(left: &str, right: &str) -> Result<bool, ParsingError> {
+    let (_left_context, left_node) = parse_expression(left, beginning(), &ParseOptions::default())?;
+    let (_right_context, right_node) = parse_expression(right, beginning(), &ParseOptions::default())?;
+
+    let commuted = generate_commuted_expressions(&left_node);
+    let right_node = normalize_positions(&right_node);
+    Ok(!commuted.find(&right_node).is_empty())
+}
+
+///
+/// A copy of `node` with every redundant (sign-`Positive`) `Parenthesis`
+/// node dropped. A `Negative`-sign `Parenthesis` is left in place: simply
+/// dropping it would change the value of anything but a literal operand,
+/// since negation doesn't distribute through a `Sum`/`Product` without
+/// rewriting every operand.
+///
+pub fn remove_parenthesis(node: &ExpressionNode) -> ExpressionNode {
+    match node {
+        ExpressionNode::Parenthesis { position: _, sign: SignType::Positive, inner } => remove_parenthesis(inner),
+        ExpressionNode::Parenthesis { position, sign, inner } => {
+            ExpressionNode::Parenthesis { position: position.clone(), sign: sign.clone(), inner: Box::new(remove_parenthesis(inner)) }
+        },
+        ExpressionNode::Sum { position, operands } => {
+            ExpressionNode::Sum { position: position.clone(), operands: operands.iter().map(remove_parenthesis).collect() }
+        },
+        ExpressionNode::Difference { position, operands } => {
+            ExpressionNode::Difference { position: position.clone(), operands: operands.iter().map(remove_parenthesis).collect() }
+        },
+        ExpressionNode::Product { position, operands } => {
+            ExpressionNode::Product { position: position.clone(), operands: operands.iter().map(remove_parenthesis).collect() }
+        },
+        ExpressionNode::Quotient { position, operands } => {
+            ExpressionNode::Quotient { position: position.clone(), operands: operands.iter().map(remove_parenthesis).collect() }
+        },
+        ExpressionNode::Power { position, base, exponent } => {
+            ExpressionNode::Power { position: position.clone(), base: Box::new(remove_parenthesis(base)), exponent: Box::new(remove_parenthesis(exponent)) }
+        },
+        ExpressionNode::Function { position, name, argument } => {
+            ExpressionNode::Function { position: position.clone(), name: name.clone(), argument: Box::new(remove_parenthesis(argument)) }
+        },
+        ExpressionNode::Degrees { position, inner } => {
+            ExpressionNode::Degrees { position: position.clone(), inner: Box::new(remove_parenthesis(inner)) }
+        },
+        ExpressionNode::ComparisonChain { position, operands, ops } => {
+            ExpressionNode::ComparisonChain { position: position.clone(), operands: operands.iter().map(remove_parenthesis).collect(), ops: ops.clone() }
+        },
+        other => other.clone(),
+    }
+}
+
+fn normalize_positions(node: &ExpressionNode) -> ExpressionNode {
+    let position = ParsePosition::default();
+    match node {
+        ExpressionNode::NaN => ExpressionNode::NaN,
+        ExpressionNode::Integer { position: _, value } => ExpressionNode::Integer { position, value: *value },
+        ExpressionNode::Decimal { position: _, value } => ExpressionNode::Decimal { position, value: *value },
+        ExpressionNode::Variable { position: _, name } => ExpressionNode::Variable { position, name: name.clone() },
+        ExpressionNode::Constant { position: _, name } => ExpressionNode::Constant { position, name: name.clone() },
+        ExpressionNode::Parenthesis { position: _, sign, inner } => {
+            ExpressionNode::Parenthesis { position, sign: sign.clone(), inner: Box::new(normalize_positions(inner)) }
+        },
+        ExpressionNode::Sum { position: _, operands } => {
+            ExpressionNode::Sum { position, operands: operands.iter().map(normalize_positions).collect() }
+        },
+        ExpressionNode::Difference { position: _, operands } => {
+            ExpressionNode::Difference { position, operands: operands.iter().map(normalize_positions).collect() }
+        },
+        ExpressionNode::Product { position: _, operands } => {
+            ExpressionNode::Product { position, operands: operands.iter().map(normalize_positions).collect() }
+        },
+        ExpressionNode::Quotient { position: _, operands } => {
+            ExpressionNode::Quotient { position, operands: operands.iter().map(normalize_positions).collect() }
+        },
+        ExpressionNode::Power { position: _, base, exponent } => {
+            ExpressionNode::Power { position, base: Box::new(normalize_positions(base)), exponent: Box::new(normalize_positions(exponent)) }
+        },
+        ExpressionNode::Function { position: _, name, argument } => {
+            ExpressionNode::Function { position, name: name.clone(), argument: Box::new(normalize_positions(argument)) }
+        },
+        ExpressionNode::Degrees { position: _, inner } => {
+            ExpressionNode::Degrees { position, inner: Box::new(normalize_positions(inner)) }
+        },
+        ExpressionNode::ComparisonChain { position: _, operands, ops } => {
+            ExpressionNode::ComparisonChain { position, operands: operands.iter().map(normalize_positions).collect(), ops: ops.clone() }
+        },
+    }
+}
+
+fn commuted_forms(node: &ExpressionNode) -> Vec<ExpressionNode> {
+    match node {
+        ExpressionNode::Sum { position, operands } => {
+            cartesian_product(operands.iter().map(commuted_forms).collect()).into_iter()
+                .flat_map(permutations)
+                .map(|operands| ExpressionNode::Sum { position: position.clone(), operands })
+                .collect()
+        },
+        ExpressionNode::Product { position, operands } => {
+            cartesian_product(operands.iter().map(commuted_forms).collect()).into_iter()
+                .flat_map(permutations)
+                .map(|operands| ExpressionNode::Product { position: position.clone(), operands })
+                .collect()
+        },
+        ExpressionNode::Difference { position, operands } => {
+            cartesian_product(operands.iter().map(commuted_forms).collect()).into_iter()
+                .map(|operands| ExpressionNode::Difference { position: position.clone(), operands })
+                .collect()
+        },
+        ExpressionNode::Quotient { position, operands } => {
+            cartesian_product(operands.iter().map(commuted_forms).collect()).into_iter()
+                .map(|operands| ExpressionNode::Quotient { position: position.clone(), operands })
+                .collect()
+        },
+        ExpressionNode::ComparisonChain { position, operands, ops } => {
+            cartesian_product(operands.iter().map(commuted_forms).collect()).into_iter()
+                .map(|operands| ExpressionNode::ComparisonChain { position: position.clone(), operands, ops: ops.clone() })
+                .collect()
+        },
+        ExpressionNode::Parenthesis { position, sign, inner } => {
+            commuted_forms(inner).into_iter()
+                .map(|inner| ExpressionNode::Parenthesis { position: position.clone(), sign: sign.clone(), inner: Box::new(inner) })
+                .collect()
+        },
+        ExpressionNode::Power { position, base, exponent } => {
+            let exponent_forms = commuted_forms(exponent);
+            commuted_forms(base).into_iter()
+                .flat_map(|base| exponent_forms.iter().map(move |exponent| {
+                    ExpressionNode::Power { position: position.clone(), base: Box::new(base.clone()), exponent: Box::new(exponent.clone()) }
+                }).collect::<Vec<_>>())
+                .collect()
+        },
+        ExpressionNode::Function { position, name, argument } => {
+            commuted_forms(argument).into_iter()
+                .map(|argument| ExpressionNode::Function { position: position.clone(), name: name.clone(), argument: Box::new(argument) })
+                .collect()
+        },
+        ExpressionNode::Degrees { position, inner } => {
+            commuted_forms(inner).into_iter()
+                .map(|inner| ExpressionNode::Degrees { position: position.clone(), inner: Box::new(inner) })
+                .collect()
+        },
+        other => vec!(other.clone()),
+    }
+}
+
+///
+/// Every ordering of `items`, using [LinkList::permute] rather than
+/// re-implementing permutation generation here.
+///
+fn permutations(items: Vec<ExpressionNode>) -> Vec<Vec<ExpressionNode>> {
+    if items.is_empty() {
+        return vec!(vec!());
+    }
+
+    let list: LinkList<ExpressionNode> = items.into_iter().collect();
+    list.permute().into_iter().map(|ordering| ordering.to_vec()).collect()
+}
+
+fn cartesian_product(lists: Vec<Vec<ExpressionNode>>) -> Vec<Vec<ExpressionNode>> {
+    lists.into_iter().fold(vec!(vec!()), |acc, list| {
+        acc.into_iter().flat_map(|prefix| {
+            list.iter().map(move |item| {
+                let mut combo = prefix.clone();
+                combo.push(item.clone());
+                combo
+            }).collect::<Vec<_>>()
+        }).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::node::Evaluate;
+
+    #[test]
+    fn test_generate_commuted_expressions_of_sum_has_every_ordering() {
+        let (_context, node) = parse_expression("1 + 2 + 3", beginning(), &ParseOptions::default()).unwrap();
+        let commuted = generate_commuted_expressions(&node);
+
+        // 3! = 6 orderings of a 3-operand Sum
+        assert_eq!(6, commuted.size());
+    }
+
+    #[test]
+    fn test_are_expressions_equivalent_under_commutation() {
+        assert_eq!(Ok(true), are_expressions_equivalent("1 + 2 + 3", "3 + 2 + 1"));
+    }
+
+    #[test]
+    fn test_are_expressions_not_equivalent_across_different_operators() {
+        assert_eq!(Ok(false), are_expressions_equivalent("1 + 2 + 3", "1 - 2 - 3"));
+    }
+
+    #[test]
+    fn test_remove_parenthesis_drops_redundant_positive_wrapping() {
+        let (_context, node) = parse_expression("(1 + 2) * 3", beginning(), &ParseOptions::default()).unwrap();
+        let simplified = remove_parenthesis(&node);
+
+        // the Parenthesis is gone, leaving a Product of the Sum and 3 directly
+        assert!(!simplified.walk_preorder().iter().any(|node| matches!(node, ExpressionNode::Parenthesis { .. })));
+        assert_eq!(node.evaluate(), simplified.evaluate());
+    }
+
+    #[test]
+    fn test_remove_parenthesis_preserves_negative_sign() {
+        let (_context, node) = parse_expression("-(1 + 2)", beginning(), &ParseOptions::default()).unwrap();
+        let simplified = remove_parenthesis(&node);
+
+        assert!(matches!(simplified, ExpressionNode::Parenthesis { sign: SignType::Negative, .. }));
+    }
+}
@@ -3,13 +3,85 @@ use crate::expression::position::ParsePosition;
 use crate::expression::node::Position;
 
 
+///
+/// The specific reason a [ParsingError::Number] failed, so callers can
+/// give a more helpful message than a generic "error parsing number".
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberError {
+    /// no digits were found where at least one was required (e.g. a bare `.`)
+    NoDigits,
+    /// more than one decimal point was found (e.g. `1.2.3`)
+    MultipleDecimalPoints,
+    /// an `e`/`E` exponent marker was found with no digits following it (e.g. `1e`)
+    ExponentWithoutDigits,
+    /// the digits were valid, but the value is too large (or too small) to represent;
+    /// `Some` when this came from an actual `str::parse` failure (e.g. an `i32`
+    /// literal too large to fit), `None` when it came from a semantic check against
+    /// `ParseOptions` (e.g. `max_abs_value`) with no underlying parse error to chain to
+    OutOfRange(Option<NumberParseError>),
+}
+impl std::fmt::Display for NumberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberError::NoDigits => f.write_str("no digits"),
+            NumberError::MultipleDecimalPoints => f.write_str("multiple decimal points"),
+            NumberError::ExponentWithoutDigits => f.write_str("exponent without digits"),
+            NumberError::OutOfRange(_source) => f.write_str("value out of range"),
+        }
+    }
+}
+
+///
+/// A `str::parse` failure (e.g. `"999999999999".parse::<i32>()` overflowing),
+/// wrapped so [ParsingError::source] has a concrete [std::error::Error] to
+/// chain to. Keeps only the original error's message rather than the original
+/// error type, since `NumberError`/`ParsingError` derive `Clone`/`PartialEq`
+/// and the standard library's numeric parse errors don't all implement both.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberParseError {
+    message: String,
+}
+impl NumberParseError {
+    pub(crate) fn new(source: impl std::error::Error) -> NumberParseError {
+        NumberParseError { message: source.to_string() }
+    }
+}
+impl std::fmt::Display for NumberParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+impl std::error::Error for NumberParseError {}
+
 #[allow(unused)]
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParsingError {
     Unknown(ParsePosition),
     EndOfInput(ParsePosition),
     ExtraInput(ParsePosition),
-    Number(ParsePosition),
+    Number(ParsePosition, NumberError),
+    /// an operator (e.g. `+`) was consumed but no right operand followed it
+    /// (e.g. `"1 +"`); the position points at the operator itself, not at
+    /// the end of input, so callers can underline exactly what's missing
+    MissingOperand(ParsePosition, char),
+    /// an n-ary node (e.g. a `Sum`) collected more operands than
+    /// `ParseOptions::max_operands` allows; the position spans the operands
+    /// seen so far, up to and including the one that tipped it over the limit
+    TooManyOperands(ParsePosition),
+    /// an identifier immediately followed by `(` named a function that isn't
+    /// one of the built-ins `apply_function` knows how to evaluate; the
+    /// position spans the whole call, name included
+    UnknownFunction(ParsePosition, String),
+    /// a `)` was found with no matching open `(` (e.g. `"1 + 2)"`); the
+    /// position points at the stray closing paren itself
+    UnbalancedParenthesis(ParsePosition),
+    /// an evaluation-time failure (divide-by-zero, a function argument
+    /// outside its domain, an unbound variable) found by [ExpressionNode::try_evaluate](crate::expression::node::ExpressionNode::try_evaluate);
+    /// the position spans the operation that failed, not the whole expression
+    Evaluation(ParsePosition, EvaluationError),
+    Io(String),
 }
 impl std::fmt::Display for ParsingError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -23,13 +95,38 @@ impl std::fmt::Display for ParsingError {
             ParsingError::ExtraInput(position) => {
                 f.write_fmt(format_args!("Unexpected input after expression at {:?}", &position))
             },
-            ParsingError::Number(position) => {
-                f.write_fmt(format_args!("Error parsing number at {:?}", &position))
+            ParsingError::Number(position, kind) => {
+                f.write_fmt(format_args!("Error parsing number at {:?}: {}", &position, kind))
             },
+            ParsingError::MissingOperand(position, operator) => {
+                f.write_fmt(format_args!("Error parsing expression at {:?}: missing right operand for '{}'", &position, operator))
+            },
+            ParsingError::TooManyOperands(position) => {
+                f.write_fmt(format_args!("Error parsing expression at {:?}: too many operands", &position))
+            },
+            ParsingError::UnknownFunction(position, name) => {
+                f.write_fmt(format_args!("Error parsing expression at {:?}: unknown function '{}'", &position, name))
+            },
+            ParsingError::UnbalancedParenthesis(position) => {
+                f.write_fmt(format_args!("Error parsing expression at {:?}: unmatched closing parenthesis", &position))
+            },
+            ParsingError::Evaluation(position, kind) => {
+                f.write_fmt(format_args!("Error evaluating expression at {:?}: {}", &position, kind))
+            },
+            ParsingError::Io(msg) => {
+                f.write_fmt(format_args!("Error reading input: {}", &msg))
+            },
+        }
+    }
+}
+impl std::error::Error for ParsingError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParsingError::Number(_position, NumberError::OutOfRange(Some(source))) => Some(source),
+            _ => None,
         }
     }
 }
-impl std::error::Error for ParsingError {}
 
 impl Position for ParsingError {
     fn position(&self) -> ParsePosition {
@@ -37,13 +134,41 @@ impl Position for ParsingError {
             ParsingError::Unknown(position) => position.clone(),
             ParsingError::EndOfInput(position) => position.clone(),
             ParsingError::ExtraInput(position) => position.clone(),
-            ParsingError::Number(position) => position.clone(),
+            ParsingError::Number(position, _kind) => position.clone(),
+            ParsingError::MissingOperand(position, _operator) => position.clone(),
+            ParsingError::TooManyOperands(position) => position.clone(),
+            ParsingError::UnknownFunction(position, _name) => position.clone(),
+            ParsingError::UnbalancedParenthesis(position) => position.clone(),
+            ParsingError::Evaluation(position, _kind) => position.clone(),
+            ParsingError::Io(_msg) => ParsePosition::default(),
         }
     }
 }
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum EvaluationError {
     Number{msg: String},
+    /// the right-hand side of a division was zero
+    DivideByZero,
+    /// a function argument (e.g. a negative `sqrt` argument) was outside
+    /// the domain that function is defined for
+    DomainError{msg: String},
+    /// a [Variable](crate::expression::node::ExpressionNode::Variable) had no entry in the
+    /// bindings passed to [try_evaluate](crate::expression::node::ExpressionNode::try_evaluate)
+    UnboundVariable{name: String},
+    /// an `Integer` `Add`/`Sub`/`Mul`/`Power` would have wrapped or panicked
+    /// because the exact result doesn't fit `IntegerType`
+    Overflow,
+}
+impl std::fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvaluationError::Number{msg} => f.write_str(msg),
+            EvaluationError::DivideByZero => f.write_str("divide by zero"),
+            EvaluationError::DomainError{msg} => f.write_str(msg),
+            EvaluationError::UnboundVariable{name} => f.write_fmt(format_args!("unbound variable '{}'", name)),
+            EvaluationError::Overflow => f.write_str("integer overflow"),
+        }
+    }
 }
\ No newline at end of file
@@ -2,4 +2,11 @@ pub mod error;
 pub mod node;
 pub mod parse;
 pub mod position;
-pub mod value;
\ No newline at end of file
+pub mod token;
+pub mod value;
+
+///
+/// Re-exported for convenience, so a one-shot parse-and-evaluate caller doesn't need
+/// to reach into `expression::parse` just to call [parse::evaluate_str].
+///
+pub use parse::evaluate_str;
\ No newline at end of file
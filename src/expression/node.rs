@@ -1,17 +1,82 @@
 //!
 //! Abstract syntax tree for expressions
 //!
+use std::collections::HashMap;
 use std::fmt::{Display, write};
 
-use super::{value::{ExpressionValue, DecimalType, IntegerType, SignType, Power}, position::ParsePosition};
+use super::{value::{ExpressionValue, DecimalType, IntegerType, SignType, Power, ComparisonOp, Rounding, infinity_sign_to_f64, value_from_f64}, position::{ParsePosition, PositionError}, error::{ParsingError, EvaluationError}};
 
 ///
-/// evaluate an expression node to get an expression value
+/// Evaluate an expression node to get an expression value.
+///
+/// Takes `&self` rather than consuming the node, so the same tree can be
+/// evaluated more than once (e.g. evaluating the same node twice, or as
+/// part of [evaluate_all]).
 ///
 pub trait Evaluate {
     fn evaluate(&self) -> ExpressionValue;
 }
 
+///
+/// Evaluate every node in `nodes`, in order, returning their values.
+///
+pub fn evaluate_all(nodes: &[ExpressionNode]) -> Vec<ExpressionValue> {
+    nodes.iter().map(|node| node.evaluate()).collect()
+}
+
+///
+/// Like [evaluate_all], but evaluates `exprs` across a rayon thread pool,
+/// for server workloads evaluating many independent expressions. `ExpressionNode`
+/// is `Send + Sync` (it's a tree of owned `Box`/`Vec` data with no shared
+/// mutable state), so each expression can be evaluated on whichever thread
+/// picks it up; results are returned in the same order as `exprs`.
+///
+#[cfg(feature = "rayon")]
+pub fn evaluate_batch_parallel(exprs: &[ExpressionNode]) -> Vec<ExpressionValue> {
+    use rayon::prelude::*;
+    exprs.par_iter().map(|node| node.evaluate()).collect()
+}
+
+///
+/// `n!` as a `u128`, to match [count_commuted_forms]'s return type.
+///
+fn factorial(n: usize) -> u128 {
+    (1..=n as u128).product()
+}
+
+///
+/// Count the number of distinct expressions reachable from `node` by
+/// reordering the operands of its `Sum`/`Product` nodes (the commutative
+/// operators), without materializing any of them. A `Sum`/`Product` with
+/// `n` operands contributes a factor of `n!` (every ordering of its
+/// operands), multiplied by the recursive count of each operand, since
+/// nested commutative nodes have forms of their own. `Difference` and
+/// `Quotient` are order-sensitive and contribute no factor of their own,
+/// but still recurse into their operands.
+///
+pub fn count_commuted_forms(node: &ExpressionNode) -> u128 {
+    match node {
+        ExpressionNode::Sum { position: _, operands } | ExpressionNode::Product { position: _, operands } => {
+            operands.iter().fold(factorial(operands.len()), |count, operand| count * count_commuted_forms(operand))
+        },
+        ExpressionNode::Difference { position: _, operands } | ExpressionNode::Quotient { position: _, operands } => {
+            operands.iter().fold(1u128, |count, operand| count * count_commuted_forms(operand))
+        },
+        ExpressionNode::Parenthesis { position: _, sign: _, inner } => count_commuted_forms(inner),
+        ExpressionNode::Power { position: _, base, exponent } => count_commuted_forms(base) * count_commuted_forms(exponent),
+        ExpressionNode::Function { position: _, name: _, argument } => count_commuted_forms(argument),
+        ExpressionNode::Degrees { position: _, inner } => count_commuted_forms(inner),
+        ExpressionNode::ComparisonChain { position: _, operands, ops: _ } => {
+            operands.iter().fold(1u128, |count, operand| count * count_commuted_forms(operand))
+        },
+        ExpressionNode::NaN
+        | ExpressionNode::Integer { position: _, value: _ }
+        | ExpressionNode::Decimal { position: _, value: _ }
+        | ExpressionNode::Variable { position: _, name: _ }
+        | ExpressionNode::Constant { position: _, name: _ } => 1,
+    }
+}
+
 ///
 /// Get the start and end position of the expression
 /// in the original source.
@@ -20,6 +85,20 @@ pub trait Position {
     fn position(&self) -> ParsePosition;
 }
 
+///
+/// Options controlling [ExpressionNode::evaluate_with_options].
+///
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EvalOptions {
+    /// when true, a [ExpressionNode::Product] with a literal zero operand
+    /// evaluates to `0` without evaluating its other operands, suppressing
+    /// any `NaN` (e.g. from a `1 / 0` operand) they would otherwise produce
+    pub short_circuit_zero_product: bool,
+    /// how an [ExpressionNode::Quotient] between two integer operands rounds
+    /// when the exact quotient isn't itself an integer; see [Rounding]
+    pub division_rounding: Rounding,
+}
+
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExpressionNode {
@@ -32,119 +111,2731 @@ pub enum ExpressionNode {
     Product{ position: ParsePosition, operands: Vec<ExpressionNode> },
     Quotient{ position: ParsePosition, operands: Vec<ExpressionNode> },
     Power{ position: ParsePosition, base: Box<ExpressionNode>, exponent: Box<ExpressionNode> },
+    // produced by `parse_value` for a bare identifier that doesn't match a
+    // known constant name or an identifier immediately followed by `(`; also
+    // buildable programmatically (e.g. by `evaluate_table`) and substituted
+    // before evaluation.
+    Variable{ position: ParsePosition, name: String },
+    // a named constant recognized directly by `parse_value` (currently "pi",
+    // "π", and "e"); see `constant_value` for the name-to-value mapping.
+    // Unlike `Variable`, an unrecognized name here means a broken program
+    // invariant rather than an ordinary unbound reference.
+    Constant{ position: ParsePosition, name: String },
+    // produced by `parse_value` for an identifier immediately followed by
+    // `(` (e.g. `sqrt(4)`), as well as for the `√` radical symbol (which
+    // always uses the name "sqrt"); `name` must be one of the built-ins
+    // `is_known_function_name` recognizes, since `parse_value` rejects any
+    // other name with `ParsingError::UnknownFunction`.
+    Function{ position: ParsePosition, name: String, argument: Box<ExpressionNode> },
+    // postfix '°' (degree symbol), converting its operand from degrees to radians.
+    Degrees{ position: ParsePosition, inner: Box<ExpressionNode> },
+    // produced by `parse_comparison` for a run of `<`/`<=`/`>`/`>=`/`==`/`!=`
+    // comparisons (e.g. `a < b < c`), as well as buildable programmatically.
+    // `operands.len()` is always `ops.len() + 1`; `ops[i]` relates `operands[i]`
+    // and `operands[i + 1]`, chained with `&&` (e.g. `a < b < c` is
+    // `(a < b) && (b < c)`), evaluating each operand once.
+    ComparisonChain{ position: ParsePosition, operands: Vec<ExpressionNode>, ops: Vec<ComparisonOp> },
 }
 
 impl Evaluate for ExpressionNode {
     fn evaluate(&self) -> ExpressionValue {
+        self.evaluate_with(&HashMap::new())
+    }
+}
+
+///
+/// Apply a named built-in single-argument function to `value`, returning
+/// [ExpressionValue::NaN] for an unrecognized name. Shared by [ExpressionNode::evaluate_with_hook]
+/// and [ExpressionNode::evaluate_iterative] so the built-in set only needs to be listed once.
+///
+fn apply_function(name: &str, value: ExpressionValue) -> ExpressionValue {
+    let argument = match value {
+        ExpressionValue::NaN { reason } => return ExpressionValue::NaN { reason },
+        ExpressionValue::Integer { value } => value as f64,
+        ExpressionValue::Decimal { value } => value,
+        ExpressionValue::Rational { numerator, denominator } => numerator as f64 / denominator as f64,
+        ExpressionValue::Infinity { sign } => infinity_sign_to_f64(&sign),
+    };
+    match name {
+        "sqrt" => value_from_f64(argument.sqrt()),
+        "sin" => value_from_f64(argument.sin()),
+        "cos" => value_from_f64(argument.cos()),
+        "tan" => value_from_f64(argument.tan()),
+        "exp" => value_from_f64(argument.exp()),
+        "ln" => value_from_f64(argument.ln()),
+        "log10" => value_from_f64(argument.log10()),
+        "abs" => value_from_f64(argument.abs()),
+        "floor" => value_from_f64(argument.floor()),
+        "ceil" => value_from_f64(argument.ceil()),
+        "round" => value_from_f64(argument.round()),
+        _ => ExpressionValue::NaN { reason: None }, // unknown function
+    }
+}
+
+///
+/// Used by [ExpressionNode::try_evaluate] to turn an [ExpressionValue::NaN] into an `Err`
+/// instead of letting it propagate silently: a `NaN` with a specific `reason` (e.g. a
+/// divide-by-zero) keeps that reason, while a `NaN` with no reason (e.g. `sqrt` of a negative
+/// number, or an unrecognized constant/function) is reported as [EvaluationError::DomainError].
+///
+fn to_result(value: ExpressionValue, position: &ParsePosition) -> Result<ExpressionValue, ParsingError> {
+    match value {
+        ExpressionValue::NaN { reason: Some(reason) } => Err(ParsingError::Evaluation(position.clone(), reason)),
+        ExpressionValue::NaN { reason: None } => Err(ParsingError::Evaluation(position.clone(), EvaluationError::DomainError {
+            msg: "result is not a number".to_string()
+        })),
+        other => Ok(other),
+    }
+}
+
+///
+/// True if `name` is one of the built-in single-argument functions
+/// [apply_function] knows how to evaluate; used by `parse_value` to reject
+/// an unrecognized function name at parse time rather than letting it
+/// silently evaluate to [ExpressionValue::NaN].
+///
+pub(crate) fn is_known_function_name(name: &str) -> bool {
+    matches!(name, "sqrt" | "sin" | "cos" | "tan" | "exp" | "ln" | "log10" | "abs" | "floor" | "ceil" | "round")
+}
+
+///
+/// The value of a named constant recognized by `parse_value` and
+/// [ExpressionNode::Constant], or `None` for an unrecognized name. `"pi"`
+/// and `"π"` both name [std::f64::consts::PI]; `"e"` names [std::f64::consts::E].
+///
+pub(crate) fn constant_value(name: &str) -> Option<DecimalType> {
+    match name {
+        "pi" | "π" => Some(std::f64::consts::PI),
+        "e" => Some(std::f64::consts::E),
+        _ => None,
+    }
+}
+
+///
+/// True if `node` is a literal zero, i.e. `Integer { value: 0 }` or `Decimal { value: 0.0 }`.
+///
+fn is_literal_zero(node: &ExpressionNode) -> bool {
+    match node {
+        ExpressionNode::Integer { position: _, value } => *value == 0,
+        ExpressionNode::Decimal { position: _, value } => *value == 0.0,
+        _ => false,
+    }
+}
+
+impl ExpressionNode {
+    ///
+    /// Compute a conservative upper bound on the magnitude (absolute value)
+    /// that evaluating this subtree could produce.
+    /// This is intended to flag expressions that are likely to overflow `i32`
+    /// before actually evaluating them.
+    ///
+    /// Returns `None` when the subtree's magnitude cannot be determined
+    /// (for example, `NaN`, a `Variable`, `tan`, a quotient whose divisors
+    /// aren't all `Integer` literals, or a power whose exponent isn't a
+    /// non-negative literal).
+    ///
+    pub fn magnitude_bound(&self) -> Option<f64> {
+        match self {
+            ExpressionNode::NaN => None,
+            ExpressionNode::Integer { position: _, value } => Some((*value as f64).abs()),
+            ExpressionNode::Decimal { position: _, value } => Some(value.abs()),
+            ExpressionNode::Parenthesis { position: _, sign: _, inner } => inner.magnitude_bound(),
+            ExpressionNode::Sum { position: _, operands } | ExpressionNode::Difference { position: _, operands } => {
+                let mut bound = 0f64;
+                for operand in operands {
+                    bound += operand.magnitude_bound()?;
+                }
+                Some(bound)
+            },
+            ExpressionNode::Product { position: _, operands } => {
+                let mut bound = 1f64;
+                for operand in operands {
+                    bound *= operand.magnitude_bound()?;
+                }
+                Some(bound)
+            },
+            ExpressionNode::Quotient { position: _, operands } => {
+                // dividing by a whole number never grows the numerator's magnitude, but
+                // dividing by a fraction can (1 / 0.5 is 2) and no lower bound on a
+                // divisor is tracked, so only all-`Integer`-literal divisors give a bound.
+                if operands[1..].iter().all(|operand| matches!(operand, ExpressionNode::Integer { .. })) {
+                    operands[0].magnitude_bound()
+                } else {
+                    None
+                }
+            },
+            ExpressionNode::Power { position: _, base, exponent } => {
+                // |b|^e <= B^e for a non-negative exponent e, but a negative exponent
+                // can make a small base arbitrarily large (0.5^-10 is 1024), so only a
+                // non-negative literal exponent gives a bound.
+                let base_bound = base.magnitude_bound()?;
+                match exponent.as_ref() {
+                    ExpressionNode::Integer { position: _, value } if *value >= 0 => Some(base_bound.powf(*value as f64)),
+                    ExpressionNode::Decimal { position: _, value } if *value >= 0.0 => Some(base_bound.powf(*value)),
+                    _ => None,
+                }
+            },
+            ExpressionNode::Variable { position: _, name: _ } => None,
+            // a recognized constant is a fixed, known value; an unrecognized
+            // name is treated the same as an unbound `Variable`.
+            ExpressionNode::Constant { position: _, name } => constant_value(name).map(|value| value.abs()),
+            ExpressionNode::Function { position: _, name, argument } => {
+                let argument_bound = argument.magnitude_bound()?;
+                match name.as_str() {
+                    // sqrt is monotonic over non-negative magnitudes, so sqrt of the
+                    // argument's bound is itself a safe, conservative bound.
+                    "sqrt" => Some(argument_bound.sqrt()),
+                    // exp grows faster than its argument, so exp of the bound is a safe bound.
+                    "exp" => Some(argument_bound.exp()),
+                    // ln/log10 grow without bound as the argument approaches 0 and no
+                    // lower bound on the argument is tracked, so only a literal argument
+                    // (whose bound is its exact magnitude) gives a bound.
+                    "ln" | "log10" => match argument.as_ref() {
+                        ExpressionNode::Integer { .. } | ExpressionNode::Decimal { .. } => {
+                            let logarithm = if name.as_str() == "ln" { argument_bound.ln() } else { argument_bound.log10() };
+                            Some(argument_bound.max(logarithm.abs()))
+                        },
+                        _ => None,
+                    },
+                    // sin/cos never exceed 1 in magnitude, regardless of their argument.
+                    "sin" | "cos" => Some(1.0),
+                    // tan is unbounded near its asymptotes, so no safe bound can be given.
+                    "tan" => None,
+                    // abs never exceeds the argument's own magnitude.
+                    "abs" => Some(argument_bound),
+                    // floor/ceil/round never move a value more than 1 away from itself.
+                    "floor" | "ceil" | "round" => Some(argument_bound + 1.0),
+                    // unknown function: fall back to the argument's own bound.
+                    _ => Some(argument_bound),
+                }
+            },
+            // degrees-to-radians is just a linear scale, so scaling the
+            // operand's bound by the same factor is itself a safe bound.
+            ExpressionNode::Degrees { position: _, inner } => Some(inner.magnitude_bound()? * std::f64::consts::PI / 180.0),
+            // a comparison chain always evaluates to 0 or 1 (see `evaluate_with_hook`).
+            ExpressionNode::ComparisonChain { position: _, operands: _, ops: _ } => Some(1.0),
+        }
+    }
+
+    ///
+    /// Build a copy of this subtree with every `Variable` node named `name`
+    /// replaced by a `Decimal` literal holding `value`.
+    ///
+    fn substitute(&self, name: &str, value: DecimalType) -> ExpressionNode {
+        match self {
+            ExpressionNode::Variable { position, name: var_name } if var_name == name => {
+                ExpressionNode::Decimal { position: position.clone(), value }
+            },
+            ExpressionNode::Parenthesis { position, sign, inner } => {
+                ExpressionNode::Parenthesis { position: position.clone(), sign: sign.clone(), inner: Box::new(inner.substitute(name, value)) }
+            },
+            ExpressionNode::Sum { position, operands } => {
+                ExpressionNode::Sum { position: position.clone(), operands: operands.iter().map(|operand| operand.substitute(name, value)).collect() }
+            },
+            ExpressionNode::Difference { position, operands } => {
+                ExpressionNode::Difference { position: position.clone(), operands: operands.iter().map(|operand| operand.substitute(name, value)).collect() }
+            },
+            ExpressionNode::Product { position, operands } => {
+                ExpressionNode::Product { position: position.clone(), operands: operands.iter().map(|operand| operand.substitute(name, value)).collect() }
+            },
+            ExpressionNode::Quotient { position, operands } => {
+                ExpressionNode::Quotient { position: position.clone(), operands: operands.iter().map(|operand| operand.substitute(name, value)).collect() }
+            },
+            ExpressionNode::Power { position, base, exponent } => {
+                ExpressionNode::Power { position: position.clone(), base: Box::new(base.substitute(name, value)), exponent: Box::new(exponent.substitute(name, value)) }
+            },
+            ExpressionNode::Function { position, name: function_name, argument } => {
+                ExpressionNode::Function { position: position.clone(), name: function_name.clone(), argument: Box::new(argument.substitute(name, value)) }
+            },
+            ExpressionNode::Degrees { position, inner } => {
+                ExpressionNode::Degrees { position: position.clone(), inner: Box::new(inner.substitute(name, value)) }
+            },
+            other => other.clone(),
+        }
+    }
+
+    ///
+    /// True if this subtree contains a `Variable` node named `name`,
+    /// anywhere below (or at) this node; used by [fold_constants_except] to
+    /// decide whether a subtree is safe to evaluate once and replace with
+    /// its literal value.
+    ///
+    fn contains_variable(&self, name: &str) -> bool {
+        match self {
+            ExpressionNode::Variable { position: _, name: var_name } => var_name == name,
+            other => other.children().iter().any(|child| child.contains_variable(name)),
+        }
+    }
+
+    ///
+    /// Build a copy of this subtree with every subtree that does *not*
+    /// contain `var` replaced by its evaluated literal value, leaving
+    /// `var`-dependent parts symbolic. This is useful for a plotting loop
+    /// that evaluates the same expression over many values of `var`: folding
+    /// the constant parts once up front avoids recomputing them on every call
+    /// to [evaluate_table].
+    ///
+    /// The root node itself is only folded if it doesn't contain `var`; a
+    /// leaf `Variable` named `var` is always left as-is.
+    ///
+    pub fn fold_constants_except(&self, var: &str) -> ExpressionNode {
+        if !self.contains_variable(var) {
+            return match self.evaluate() {
+                ExpressionValue::Integer { value } => ExpressionNode::Integer { position: self.position(), value },
+                ExpressionValue::Decimal { value } => ExpressionNode::Decimal { position: self.position(), value },
+                ExpressionValue::Rational { numerator, denominator } => ExpressionNode::Quotient {
+                    position: self.position(),
+                    operands: vec!(
+                        ExpressionNode::Integer { position: self.position(), value: numerator as IntegerType },
+                        ExpressionNode::Integer { position: self.position(), value: denominator as IntegerType },
+                    ),
+                },
+                ExpressionValue::NaN { reason: _ } | ExpressionValue::Infinity { sign: _ } => ExpressionNode::NaN,
+            };
+        }
         match self {
-            ExpressionNode::NaN => ExpressionValue::NaN,
+            ExpressionNode::Parenthesis { position, sign, inner } => {
+                ExpressionNode::Parenthesis { position: position.clone(), sign: sign.clone(), inner: Box::new(inner.fold_constants_except(var)) }
+            },
+            ExpressionNode::Sum { position, operands } => {
+                ExpressionNode::Sum { position: position.clone(), operands: operands.iter().map(|operand| operand.fold_constants_except(var)).collect() }
+            },
+            ExpressionNode::Difference { position, operands } => {
+                ExpressionNode::Difference { position: position.clone(), operands: operands.iter().map(|operand| operand.fold_constants_except(var)).collect() }
+            },
+            ExpressionNode::Product { position, operands } => {
+                ExpressionNode::Product { position: position.clone(), operands: operands.iter().map(|operand| operand.fold_constants_except(var)).collect() }
+            },
+            ExpressionNode::Quotient { position, operands } => {
+                ExpressionNode::Quotient { position: position.clone(), operands: operands.iter().map(|operand| operand.fold_constants_except(var)).collect() }
+            },
+            ExpressionNode::Power { position, base, exponent } => {
+                ExpressionNode::Power { position: position.clone(), base: Box::new(base.fold_constants_except(var)), exponent: Box::new(exponent.fold_constants_except(var)) }
+            },
+            ExpressionNode::Function { position, name, argument } => {
+                ExpressionNode::Function { position: position.clone(), name: name.clone(), argument: Box::new(argument.fold_constants_except(var)) }
+            },
+            ExpressionNode::Degrees { position, inner } => {
+                ExpressionNode::Degrees { position: position.clone(), inner: Box::new(inner.fold_constants_except(var)) }
+            },
+            ExpressionNode::ComparisonChain { position, operands, ops } => {
+                ExpressionNode::ComparisonChain { position: position.clone(), operands: operands.iter().map(|operand| operand.fold_constants_except(var)).collect(), ops: ops.clone() }
+            },
+            // NaN, Integer, Decimal, Variable, Constant: already handled by
+            // the `contains_variable` check above (a `Variable` named `var`
+            // falls through to here unchanged; every other leaf would have
+            // already been folded).
+            other => other.clone(),
+        }
+    }
+
+    ///
+    /// Evaluate this subtree once for each value in `values`, binding the
+    /// variable named `var` to that value before evaluating.
+    ///
+    /// This is useful for tabulating a single-variable expression, e.g.
+    /// plotting `x^2` over a range of `x`.
+    ///
+    pub fn evaluate_table(&self, var: &str, values: &[DecimalType]) -> Vec<ExpressionValue> {
+        values.iter().map(|value| self.substitute(var, *value).evaluate()).collect()
+    }
+
+    ///
+    /// Evaluate this subtree using an explicit work stack instead of
+    /// recursion, producing the same result as [Evaluate::evaluate]. This
+    /// exists because a pathologically deep tree of nested `Parenthesis`
+    /// nodes would otherwise recurse once per level and risk overflowing
+    /// the call stack.
+    ///
+    pub fn evaluate_iterative(&self) -> ExpressionValue {
+        enum WorkItem<'a> {
+            // evaluate the children of this node, then combine them
+            Visit(&'a ExpressionNode),
+            // combine the `usize` values on top of the value stack, in order, for this node
+            Combine(&'a ExpressionNode, usize),
+        }
+
+        let mut work: Vec<WorkItem> = vec!(WorkItem::Visit(self));
+        let mut values: Vec<ExpressionValue> = Vec::new();
+
+        while let Some(item) = work.pop() {
+            match item {
+                WorkItem::Visit(node) => match node {
+                    ExpressionNode::NaN => values.push(ExpressionValue::NaN { reason: None }),
+                    ExpressionNode::Integer { position: _, value } => values.push(ExpressionValue::Integer { value: *value }),
+                    ExpressionNode::Decimal { position: _, value } => values.push(ExpressionValue::Decimal { value: *value }),
+                    ExpressionNode::Variable { position: _, name: _ } => values.push(ExpressionValue::NaN { reason: None }), // unbound variable
+                    ExpressionNode::Constant { position: _, name } => {
+                        values.push(constant_value(name).map_or(ExpressionValue::NaN { reason: None }, |value| ExpressionValue::Decimal { value }));
+                    },
+                    ExpressionNode::Parenthesis { position: _, sign: _, inner } => {
+                        work.push(WorkItem::Combine(node, 1));
+                        work.push(WorkItem::Visit(inner));
+                    },
+                    ExpressionNode::Sum { position: _, operands }
+                    | ExpressionNode::Difference { position: _, operands }
+                    | ExpressionNode::Product { position: _, operands }
+                    | ExpressionNode::Quotient { position: _, operands } => {
+                        work.push(WorkItem::Combine(node, operands.len()));
+                        for operand in operands.iter().rev() {
+                            work.push(WorkItem::Visit(operand));
+                        }
+                    },
+                    ExpressionNode::Power { position: _, base, exponent } => {
+                        work.push(WorkItem::Combine(node, 2));
+                        work.push(WorkItem::Visit(exponent));
+                        work.push(WorkItem::Visit(base));
+                    },
+                    ExpressionNode::Function { position: _, name: _, argument } => {
+                        work.push(WorkItem::Combine(node, 1));
+                        work.push(WorkItem::Visit(argument));
+                    },
+                    ExpressionNode::Degrees { position: _, inner } => {
+                        work.push(WorkItem::Combine(node, 1));
+                        work.push(WorkItem::Visit(inner));
+                    },
+                    ExpressionNode::ComparisonChain { position: _, operands, ops: _ } => {
+                        work.push(WorkItem::Combine(node, operands.len()));
+                        for operand in operands.iter().rev() {
+                            work.push(WorkItem::Visit(operand));
+                        }
+                    },
+                },
+                WorkItem::Combine(node, operand_count) => {
+                    let operands: Vec<ExpressionValue> = values.split_off(values.len() - operand_count);
+                    let mut operands = operands.into_iter();
+                    let result = match node {
+                        ExpressionNode::Parenthesis { position: _, sign, inner: _ } => sign * operands.next().unwrap(),
+                        // A Sum/Difference with no operands evaluates to the additive identity, 0;
+                        // a Product/Quotient with no operands evaluates to the multiplicative identity, 1.
+                        ExpressionNode::Sum { position: _, operands: _ } => match operands.next() {
+                            None => ExpressionValue::Integer { value: 0 },
+                            Some(first) => {
+                                let mut sum = first;
+                                for addend in operands { sum += addend }
+                                sum
+                            },
+                        },
+                        ExpressionNode::Difference { position: _, operands: _ } => match operands.next() {
+                            None => ExpressionValue::Integer { value: 0 },
+                            Some(first) => {
+                                let mut difference = first;
+                                for addend in operands { difference -= addend }
+                                difference
+                            },
+                        },
+                        ExpressionNode::Product { position: _, operands: _ } => match operands.next() {
+                            None => ExpressionValue::Integer { value: 1 },
+                            Some(first) => {
+                                let mut product = first;
+                                for addend in operands { product *= addend }
+                                product
+                            },
+                        },
+                        ExpressionNode::Quotient { position: _, operands: _ } => match operands.next() {
+                            None => ExpressionValue::Integer { value: 1 },
+                            Some(first) => {
+                                let mut quotient = first;
+                                for addend in operands { quotient /= addend }
+                                quotient
+                            },
+                        },
+                        ExpressionNode::Power { position: _, base: _, exponent: _ } => {
+                            let base_value = operands.next().unwrap();
+                            let exponent_value = operands.next().unwrap();
+                            base_value.power(exponent_value)
+                        },
+                        ExpressionNode::Function { position: _, name, argument: _ } => {
+                            apply_function(name, operands.next().unwrap())
+                        },
+                        ExpressionNode::Degrees { position: _, inner: _ } => {
+                            match operands.next().unwrap() {
+                                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+                                ExpressionValue::Integer { value } => ExpressionValue::Decimal { value: (value as f64) * std::f64::consts::PI / 180.0 },
+                                ExpressionValue::Decimal { value } => ExpressionValue::Decimal { value: value * std::f64::consts::PI / 180.0 },
+                                ExpressionValue::Rational { numerator, denominator } => ExpressionValue::Decimal { value: (numerator as f64 / denominator as f64) * std::f64::consts::PI / 180.0 },
+                                ExpressionValue::Infinity { sign } => ExpressionValue::Infinity { sign },
+                            }
+                        },
+                        ExpressionNode::ComparisonChain { position: _, operands: _, ops } => {
+                            let values: Vec<ExpressionValue> = operands.collect();
+                            let holds = ops.iter().zip(values.iter().zip(values.get(1..).unwrap_or(&[]).iter())).all(|(op, (left, right))| op.apply(left, right));
+                            ExpressionValue::Integer { value: if holds { 1 } else { 0 } }
+                        },
+                        _ => unreachable!("leaf nodes never produce a Combine work item"),
+                    };
+                    values.push(result);
+                },
+            }
+        }
+
+        values.pop().unwrap()
+    }
+
+    ///
+    /// Evaluate this subtree, calling `hook` with each node and its computed
+    /// value, bottom-up, as it is evaluated (children before their parent).
+    /// This is useful for profiling or tracing which operations dominate an
+    /// evaluation.
+    ///
+    pub fn evaluate_with_hook(&self, hook: &mut impl FnMut(&ExpressionNode, &ExpressionValue)) -> ExpressionValue {
+        let value = match self {
+            ExpressionNode::NaN => ExpressionValue::NaN { reason: None },
             ExpressionNode::Integer { position: _, value } => ExpressionValue::Integer { value: *value },
             ExpressionNode::Decimal { position: _, value } => ExpressionValue::Decimal { value: *value },
-            ExpressionNode::Parenthesis { position: _, sign, inner } => sign * inner.evaluate(),
+            ExpressionNode::Variable { position: _, name: _ } => ExpressionValue::NaN { reason: None }, // unbound variable
+            ExpressionNode::Constant { position: _, name } => {
+                constant_value(name).map_or(ExpressionValue::NaN { reason: None }, |value| ExpressionValue::Decimal { value })
+            },
+            ExpressionNode::Parenthesis { position: _, sign, inner } => sign * inner.evaluate_with_hook(hook),
+            // A Sum/Difference with no operands evaluates to the additive identity, 0;
+            // a Product/Quotient with no operands evaluates to the multiplicative identity, 1.
+            // This guards against indexing into an empty Vec for trees built programmatically.
             ExpressionNode::Sum { position: _, operands } => {
-                let mut sum = operands[0].evaluate();
-                for addend in operands[1..].iter() {
-                    sum += addend.evaluate()
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 0 }
+                } else {
+                    let mut sum = operands[0].evaluate_with_hook(hook);
+                    for addend in operands[1..].iter() {
+                        sum += addend.evaluate_with_hook(hook)
+                    }
+                    sum
                 }
-                sum
             },
             ExpressionNode::Difference { position: _, operands } => {
-                let mut difference = operands[0].evaluate();
-                for addend in operands[1..].iter() {
-                    difference -= addend.evaluate()
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 0 }
+                } else {
+                    let mut difference = operands[0].evaluate_with_hook(hook);
+                    for addend in operands[1..].iter() {
+                        difference -= addend.evaluate_with_hook(hook)
+                    }
+                    difference
                 }
-                difference
             },
             ExpressionNode::Product { position: _, operands } => {
-                let mut product = operands[0].evaluate();
-                for addend in operands[1..].iter() {
-                    product *= addend.evaluate()
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 }
+                } else {
+                    let mut product = operands[0].evaluate_with_hook(hook);
+                    for addend in operands[1..].iter() {
+                        product *= addend.evaluate_with_hook(hook)
+                    }
+                    product
                 }
-                product
             },
             ExpressionNode::Quotient { position: _, operands } => {
-                let mut quotient = operands[0].evaluate();
-                for addend in operands[1..].iter() {
-                    quotient /= addend.evaluate()
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 }
+                } else {
+                    let mut quotient = operands[0].evaluate_with_hook(hook);
+                    for addend in operands[1..].iter() {
+                        quotient /= addend.evaluate_with_hook(hook)
+                    }
+                    quotient
                 }
-                quotient
             },
             ExpressionNode::Power { position: _, base, exponent } => {
-                let base_value = base.evaluate();
-                let exponent_value = exponent.evaluate();
+                let base_value = base.evaluate_with_hook(hook);
+                let exponent_value = exponent.evaluate_with_hook(hook);
                 base_value.power(exponent_value)
             },
-        }
+            ExpressionNode::Function { position: _, name, argument } => {
+                apply_function(name, argument.evaluate_with_hook(hook))
+            },
+            ExpressionNode::Degrees { position: _, inner } => {
+                match inner.evaluate_with_hook(hook) {
+                    ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+                    ExpressionValue::Integer { value } => ExpressionValue::Decimal { value: (value as f64) * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Decimal { value } => ExpressionValue::Decimal { value: value * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Rational { numerator, denominator } => ExpressionValue::Decimal { value: (numerator as f64 / denominator as f64) * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Infinity { sign } => ExpressionValue::Infinity { sign },
+                }
+            },
+            ExpressionNode::ComparisonChain { position: _, operands, ops } => {
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 } // vacuously true, like the empty Sum/Product identities above
+                } else {
+                    let mut previous = operands[0].evaluate_with_hook(hook);
+                    let mut holds = true;
+                    for (op, operand) in ops.iter().zip(operands[1..].iter()) {
+                        let current = operand.evaluate_with_hook(hook);
+                        holds = holds && op.apply(&previous, &current);
+                        previous = current;
+                    }
+                    ExpressionValue::Integer { value: if holds { 1 } else { 0 } }
+                }
+            },
+        };
+        hook(self, &value);
+        value
     }
-}
 
-impl Position for ExpressionNode {
-    fn position(&self) -> ParsePosition {
+    ///
+    /// Evaluate this subtree, resolving each [Variable](ExpressionNode::Variable)
+    /// by looking up its name in `bindings`; a name with no entry evaluates to
+    /// [ExpressionValue::NaN], same as [evaluate](ExpressionNode::evaluate)'s
+    /// unbound-variable behavior. This is what lets a tree be parsed once
+    /// (e.g. `x * 2 + y`) and evaluated repeatedly against different bindings.
+    ///
+    pub fn evaluate_with(&self, bindings: &HashMap<String, ExpressionValue>) -> ExpressionValue {
         match self {
-            ExpressionNode::NaN => ParsePosition::default(),
-            ExpressionNode::Integer { position, value: _ } => position.clone(),
-            ExpressionNode::Decimal { position, value: _ } => position.clone(),
-            ExpressionNode::Parenthesis { position, sign: _, inner: _ } => position.clone(),
-            ExpressionNode::Sum { position, operands: _ } => position.clone(),
-            ExpressionNode::Difference { position, operands: _ } => position.clone(),
-            ExpressionNode::Product { position, operands: _ } => position.clone(),
-            ExpressionNode::Quotient { position, operands: _ } => position.clone(),
-            ExpressionNode::Power { position, base: _, exponent: _ } => position.clone(),
+            ExpressionNode::NaN => ExpressionValue::NaN { reason: None },
+            ExpressionNode::Integer { position: _, value } => ExpressionValue::Integer { value: *value },
+            ExpressionNode::Decimal { position: _, value } => ExpressionValue::Decimal { value: *value },
+            ExpressionNode::Variable { position: _, name } => {
+                bindings.get(name).cloned().unwrap_or(ExpressionValue::NaN { reason: None })
+            },
+            ExpressionNode::Constant { position: _, name } => {
+                constant_value(name).map_or(ExpressionValue::NaN { reason: None }, |value| ExpressionValue::Decimal { value })
+            },
+            ExpressionNode::Parenthesis { position: _, sign, inner } => sign * inner.evaluate_with(bindings),
+            ExpressionNode::Sum { position: _, operands } => {
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 0 }
+                } else {
+                    let mut sum = operands[0].evaluate_with(bindings);
+                    for addend in operands[1..].iter() {
+                        sum += addend.evaluate_with(bindings)
+                    }
+                    sum
+                }
+            },
+            ExpressionNode::Difference { position: _, operands } => {
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 0 }
+                } else {
+                    let mut difference = operands[0].evaluate_with(bindings);
+                    for addend in operands[1..].iter() {
+                        difference -= addend.evaluate_with(bindings)
+                    }
+                    difference
+                }
+            },
+            ExpressionNode::Product { position: _, operands } => {
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 }
+                } else {
+                    let mut product = operands[0].evaluate_with(bindings);
+                    for addend in operands[1..].iter() {
+                        product *= addend.evaluate_with(bindings)
+                    }
+                    product
+                }
+            },
+            ExpressionNode::Quotient { position: _, operands } => {
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 }
+                } else {
+                    let mut quotient = operands[0].evaluate_with(bindings);
+                    for addend in operands[1..].iter() {
+                        quotient /= addend.evaluate_with(bindings)
+                    }
+                    quotient
+                }
+            },
+            ExpressionNode::Power { position: _, base, exponent } => {
+                let base_value = base.evaluate_with(bindings);
+                let exponent_value = exponent.evaluate_with(bindings);
+                base_value.power(exponent_value)
+            },
+            ExpressionNode::Function { position: _, name, argument } => {
+                apply_function(name, argument.evaluate_with(bindings))
+            },
+            ExpressionNode::Degrees { position: _, inner } => {
+                match inner.evaluate_with(bindings) {
+                    ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+                    ExpressionValue::Integer { value } => ExpressionValue::Decimal { value: (value as f64) * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Decimal { value } => ExpressionValue::Decimal { value: value * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Rational { numerator, denominator } => ExpressionValue::Decimal { value: (numerator as f64 / denominator as f64) * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Infinity { sign } => ExpressionValue::Infinity { sign },
+                }
+            },
+            ExpressionNode::ComparisonChain { position: _, operands, ops } => {
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 }
+                } else {
+                    let mut previous = operands[0].evaluate_with(bindings);
+                    let mut holds = true;
+                    for (op, operand) in ops.iter().zip(operands[1..].iter()) {
+                        let current = operand.evaluate_with(bindings);
+                        holds = holds && op.apply(&previous, &current);
+                        previous = current;
+                    }
+                    ExpressionValue::Integer { value: if holds { 1 } else { 0 } }
+                }
+            },
         }
     }
-}
 
-impl Display for ExpressionNode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///
+    /// Evaluate this subtree like [evaluate_with](ExpressionNode::evaluate_with)
+    /// (with no variable bindings), but honoring `options`.
+    ///
+    /// With [EvalOptions::short_circuit_zero_product] set, a [Product](ExpressionNode::Product)
+    /// containing a literal zero operand (see `is_literal_zero`) evaluates to
+    /// `0` directly, without evaluating its other operands. This is a real
+    /// behavior change, not just a performance tweak: an operand that would
+    /// otherwise have evaluated to [ExpressionValue::NaN] (e.g. `1 / 0`) is
+    /// never evaluated, so the `NaN` it would have produced is suppressed and
+    /// the overall result is `0` instead.
+    ///
+    pub fn evaluate_with_options(&self, options: &EvalOptions) -> ExpressionValue {
         match self {
-            ExpressionNode::NaN => f.write_str(&ExpressionValue::NaN.to_string()),
-            ExpressionNode::Integer { position: _, value } => f.write_fmt(format_args!("{}", &value)),
-            ExpressionNode::Decimal { position: _, value } => f.write_fmt(format_args!("{}", &value)),
-            ExpressionNode::Parenthesis { position: _, sign, inner } => {
-                match sign {
-                    SignType::Negative => f.write_fmt(format_args!("-({})", &inner)),
-                    SignType::Positive => f.write_fmt(format_args!("({})", &inner)),
-                }
+            ExpressionNode::Product { position: _, operands } if options.short_circuit_zero_product
+                && operands.iter().any(is_literal_zero) => {
+                ExpressionValue::Integer { value: 0 }
             },
+            ExpressionNode::Parenthesis { position: _, sign, inner } => sign * inner.evaluate_with_options(options),
             ExpressionNode::Sum { position: _, operands } => {
-                if operands.len() > 0 {
-                    write(f, format_args!("{}", &operands[0]))?;
-                    for operand in operands {
-                        write(f, format_args!(" + {}", operand))?;
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 0 }
+                } else {
+                    let mut sum = operands[0].evaluate_with_options(options);
+                    for addend in operands[1..].iter() {
+                        sum += addend.evaluate_with_options(options)
                     }
+                    sum
                 }
-                Ok(())
             },
             ExpressionNode::Difference { position: _, operands } => {
-                if operands.len() > 0 {
-                    write(f, format_args!("{}", &operands[0]))?;
-                    for operand in operands {
-                        write(f, format_args!(" - {}", operand))?;
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 0 }
+                } else {
+                    let mut difference = operands[0].evaluate_with_options(options);
+                    for addend in operands[1..].iter() {
+                        difference -= addend.evaluate_with_options(options)
                     }
+                    difference
                 }
-                Ok(())
             },
             ExpressionNode::Product { position: _, operands } => {
-                if operands.len() > 0 {
-                    write(f, format_args!("{}", &operands[0]))?;
-                    for operand in operands {
-                        write(f, format_args!(" * {}", operand))?;
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 }
+                } else {
+                    let mut product = operands[0].evaluate_with_options(options);
+                    for addend in operands[1..].iter() {
+                        product *= addend.evaluate_with_options(options)
                     }
+                    product
                 }
-                Ok(())
             },
             ExpressionNode::Quotient { position: _, operands } => {
-                if operands.len() > 0 {
-                    write(f, format_args!("{}", &operands[0]))?;
-                    for operand in operands {
-                        write(f, format_args!(" / {}", operand))?;
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 }
+                } else {
+                    let mut quotient = operands[0].evaluate_with_options(options);
+                    for addend in operands[1..].iter() {
+                        quotient = quotient.div_rounded(&addend.evaluate_with_options(options), options.division_rounding)
                     }
+                    quotient
                 }
-                Ok(())
             },
             ExpressionNode::Power { position: _, base, exponent } => {
-                f.write_fmt(format_args!("{}^{}", &base, &exponent))
+                let base_value = base.evaluate_with_options(options);
+                let exponent_value = exponent.evaluate_with_options(options);
+                base_value.power(exponent_value)
+            },
+            ExpressionNode::Function { position: _, name, argument } => {
+                apply_function(name, argument.evaluate_with_options(options))
+            },
+            ExpressionNode::Degrees { position: _, inner } => {
+                match inner.evaluate_with_options(options) {
+                    ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+                    ExpressionValue::Integer { value } => ExpressionValue::Decimal { value: (value as f64) * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Decimal { value } => ExpressionValue::Decimal { value: value * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Rational { numerator, denominator } => ExpressionValue::Decimal { value: (numerator as f64 / denominator as f64) * std::f64::consts::PI / 180.0 },
+                    ExpressionValue::Infinity { sign } => ExpressionValue::Infinity { sign },
+                }
+            },
+            ExpressionNode::ComparisonChain { position: _, operands, ops } => {
+                if operands.is_empty() {
+                    ExpressionValue::Integer { value: 1 }
+                } else {
+                    let mut previous = operands[0].evaluate_with_options(options);
+                    let mut holds = true;
+                    for (op, operand) in ops.iter().zip(operands[1..].iter()) {
+                        let current = operand.evaluate_with_options(options);
+                        holds = holds && op.apply(&previous, &current);
+                        previous = current;
+                    }
+                    ExpressionValue::Integer { value: if holds { 1 } else { 0 } }
+                }
+            },
+            other => other.evaluate_with(&HashMap::new()),
+        }
+    }
+
+    ///
+    /// Evaluate this subtree like [evaluate_with](ExpressionNode::evaluate_with), but fail
+    /// fast: the first operation that would otherwise have produced an
+    /// [ExpressionValue::NaN] returns [ParsingError::Evaluation] instead, with the position
+    /// of the operation that failed. This is the strict counterpart to `evaluate_with` for
+    /// callers (e.g. a server endpoint) that want a `Result` rather than a silent `NaN`.
+    ///
+    /// A [Variable](ExpressionNode::Variable) with no entry in `bindings` is reported as
+    /// [EvaluationError::UnboundVariable]; a division by zero is reported as
+    /// [EvaluationError::DivideByZero] (the reason `ExpressionValue`'s `Div` already attaches
+    /// to the resulting `NaN`); any other `NaN` with no specific reason (e.g. `sqrt` of a
+    /// negative number) is reported as [EvaluationError::DomainError].
+    ///
+    pub fn try_evaluate(&self, bindings: &HashMap<String, ExpressionValue>) -> Result<ExpressionValue, ParsingError> {
+        match self {
+            ExpressionNode::NaN => Err(ParsingError::Evaluation(ParsePosition::default(), EvaluationError::DomainError { msg: "not a number".to_string() })),
+            ExpressionNode::Integer { position: _, value } => Ok(ExpressionValue::Integer { value: *value }),
+            ExpressionNode::Decimal { position: _, value } => Ok(ExpressionValue::Decimal { value: *value }),
+            ExpressionNode::Variable { position, name } => {
+                bindings.get(name).cloned().ok_or_else(|| {
+                    ParsingError::Evaluation(position.clone(), EvaluationError::UnboundVariable { name: name.clone() })
+                })
+            },
+            ExpressionNode::Constant { position, name } => {
+                to_result(
+                    constant_value(name).map_or(ExpressionValue::NaN { reason: None }, |value| ExpressionValue::Decimal { value }),
+                    position
+                )
+            },
+            ExpressionNode::Parenthesis { position: _, sign, inner } => Ok(sign * inner.try_evaluate(bindings)?),
+            ExpressionNode::Sum { position, operands } => {
+                if operands.is_empty() {
+                    Ok(ExpressionValue::Integer { value: 0 })
+                } else {
+                    let mut sum = operands[0].try_evaluate(bindings)?;
+                    for addend in operands[1..].iter() {
+                        sum += addend.try_evaluate(bindings)?;
+                        sum = to_result(sum, position)?;
+                    }
+                    Ok(sum)
+                }
+            },
+            ExpressionNode::Difference { position, operands } => {
+                if operands.is_empty() {
+                    Ok(ExpressionValue::Integer { value: 0 })
+                } else {
+                    let mut difference = operands[0].try_evaluate(bindings)?;
+                    for addend in operands[1..].iter() {
+                        difference -= addend.try_evaluate(bindings)?;
+                        difference = to_result(difference, position)?;
+                    }
+                    Ok(difference)
+                }
+            },
+            ExpressionNode::Product { position, operands } => {
+                if operands.is_empty() {
+                    Ok(ExpressionValue::Integer { value: 1 })
+                } else {
+                    let mut product = operands[0].try_evaluate(bindings)?;
+                    for addend in operands[1..].iter() {
+                        product *= addend.try_evaluate(bindings)?;
+                        product = to_result(product, position)?;
+                    }
+                    Ok(product)
+                }
+            },
+            ExpressionNode::Quotient { position, operands } => {
+                if operands.is_empty() {
+                    Ok(ExpressionValue::Integer { value: 1 })
+                } else {
+                    let mut quotient = operands[0].try_evaluate(bindings)?;
+                    for addend in operands[1..].iter() {
+                        quotient /= addend.try_evaluate(bindings)?;
+                        quotient = to_result(quotient, position)?;
+                    }
+                    Ok(quotient)
+                }
+            },
+            ExpressionNode::Power { position, base, exponent } => {
+                let base_value = base.try_evaluate(bindings)?;
+                let exponent_value = exponent.try_evaluate(bindings)?;
+                to_result(base_value.power(exponent_value), position)
+            },
+            ExpressionNode::Function { position, name, argument } => {
+                to_result(apply_function(name, argument.try_evaluate(bindings)?), position)
+            },
+            ExpressionNode::Degrees { position, inner } => {
+                match inner.try_evaluate(bindings)? {
+                    ExpressionValue::NaN { reason } => to_result(ExpressionValue::NaN { reason }, position),
+                    ExpressionValue::Integer { value } => Ok(ExpressionValue::Decimal { value: (value as f64) * std::f64::consts::PI / 180.0 }),
+                    ExpressionValue::Decimal { value } => Ok(ExpressionValue::Decimal { value: value * std::f64::consts::PI / 180.0 }),
+                    ExpressionValue::Rational { numerator, denominator } => Ok(ExpressionValue::Decimal { value: (numerator as f64 / denominator as f64) * std::f64::consts::PI / 180.0 }),
+                    ExpressionValue::Infinity { sign } => Ok(ExpressionValue::Infinity { sign }),
+                }
+            },
+            ExpressionNode::ComparisonChain { position: _, operands, ops } => {
+                if operands.is_empty() {
+                    Ok(ExpressionValue::Integer { value: 1 })
+                } else {
+                    let mut previous = operands[0].try_evaluate(bindings)?;
+                    let mut holds = true;
+                    for (op, operand) in ops.iter().zip(operands[1..].iter()) {
+                        let current = operand.try_evaluate(bindings)?;
+                        holds = holds && op.apply(&previous, &current);
+                        previous = current;
+                    }
+                    Ok(ExpressionValue::Integer { value: if holds { 1 } else { 0 } })
+                }
+            },
+        }
+    }
+
+    ///
+    /// Evaluate this subtree, also reporting whether the result is merely an
+    /// approximation: `true` if any subtree produced a `Decimal` value or
+    /// went through a built-in [Function](ExpressionNode::Function) (e.g.
+    /// `sqrt`), `false` if every operation stayed in exact integer
+    /// arithmetic.
+    ///
+    pub fn evaluate_flagged(&self) -> (ExpressionValue, bool) {
+        let mut approximate = false;
+        let value = self.evaluate_with_hook(&mut |node, value| {
+            if matches!(value, ExpressionValue::Decimal { value: _ }) || matches!(node, ExpressionNode::Function { position: _, name: _, argument: _ }) {
+                approximate = true;
+            }
+        });
+        (value, approximate)
+    }
+
+    ///
+    /// Build a copy of this subtree where every n-ary `Difference`/`Quotient`
+    /// is rewritten into nested, strictly binary nodes, left-associatively
+    /// (`[a, b, c]` becomes `Difference([Difference([a, b]), c])`), for
+    /// downstream algorithms that expect binary subtraction/division.
+    /// `Sum`/`Product` are left n-ary, since they're associative and
+    /// commutative and so don't need a fixed operand order.
+    ///
+    /// The synthesized intermediate nodes reuse their parent's position,
+    /// since they don't correspond to any single span in the original source.
+    ///
+    pub fn binarize(&self) -> ExpressionNode {
+        match self {
+            ExpressionNode::Difference { position, operands } => {
+                let mut operands = operands.iter().map(|operand| operand.binarize());
+                let first = operands.next().unwrap_or(ExpressionNode::Integer { position: position.clone(), value: 0 });
+                operands.fold(first, |left, right| {
+                    ExpressionNode::Difference { position: position.clone(), operands: vec!(left, right) }
+                })
+            },
+            ExpressionNode::Quotient { position, operands } => {
+                let mut operands = operands.iter().map(|operand| operand.binarize());
+                let first = operands.next().unwrap_or(ExpressionNode::Integer { position: position.clone(), value: 1 });
+                operands.fold(first, |left, right| {
+                    ExpressionNode::Quotient { position: position.clone(), operands: vec!(left, right) }
+                })
+            },
+            ExpressionNode::Sum { position, operands } => {
+                ExpressionNode::Sum { position: position.clone(), operands: operands.iter().map(|operand| operand.binarize()).collect() }
+            },
+            ExpressionNode::Product { position, operands } => {
+                ExpressionNode::Product { position: position.clone(), operands: operands.iter().map(|operand| operand.binarize()).collect() }
+            },
+            ExpressionNode::Parenthesis { position, sign, inner } => {
+                ExpressionNode::Parenthesis { position: position.clone(), sign: sign.clone(), inner: Box::new(inner.binarize()) }
+            },
+            ExpressionNode::Power { position, base, exponent } => {
+                ExpressionNode::Power { position: position.clone(), base: Box::new(base.binarize()), exponent: Box::new(exponent.binarize()) }
+            },
+            ExpressionNode::Function { position, name, argument } => {
+                ExpressionNode::Function { position: position.clone(), name: name.clone(), argument: Box::new(argument.binarize()) }
+            },
+            ExpressionNode::Degrees { position, inner } => {
+                ExpressionNode::Degrees { position: position.clone(), inner: Box::new(inner.binarize()) }
+            },
+            ExpressionNode::ComparisonChain { position, operands, ops } => {
+                ExpressionNode::ComparisonChain { position: position.clone(), operands: operands.iter().map(|operand| operand.binarize()).collect(), ops: ops.clone() }
+            },
+            other => other.clone(),
+        }
+    }
+
+    ///
+    /// Build a copy of this subtree with every `Integer`/`Decimal` leaf
+    /// replaced by `f` applied to its value, re-tagging the leaf as an
+    /// `Integer` or `Decimal` node based on which variant `f` returns (a
+    /// `Rational` result becomes a `Quotient` of two `Integer` nodes, and a
+    /// `NaN` or `Infinity` result becomes an `ExpressionNode::NaN`, since
+    /// there's no literal syntax for either).
+    ///
+    /// This is useful for applying a uniform transform to every literal in
+    /// an expression, e.g. unit conversion or scaling.
+    ///
+    pub fn map_numbers(&self, f: &impl Fn(ExpressionValue) -> ExpressionValue) -> ExpressionNode {
+        match self {
+            ExpressionNode::Integer { position, value } => {
+                match f(ExpressionValue::Integer { value: *value }) {
+                    ExpressionValue::Integer { value } => ExpressionNode::Integer { position: position.clone(), value },
+                    ExpressionValue::Decimal { value } => ExpressionNode::Decimal { position: position.clone(), value },
+                    ExpressionValue::Rational { numerator, denominator } => ExpressionNode::Quotient {
+                        position: position.clone(),
+                        operands: vec!(
+                            ExpressionNode::Integer { position: position.clone(), value: numerator as IntegerType },
+                            ExpressionNode::Integer { position: position.clone(), value: denominator as IntegerType },
+                        ),
+                    },
+                    ExpressionValue::NaN { reason: _ } | ExpressionValue::Infinity { sign: _ } => ExpressionNode::NaN,
+                }
+            },
+            ExpressionNode::Decimal { position, value } => {
+                match f(ExpressionValue::Decimal { value: *value }) {
+                    ExpressionValue::Integer { value } => ExpressionNode::Integer { position: position.clone(), value },
+                    ExpressionValue::Decimal { value } => ExpressionNode::Decimal { position: position.clone(), value },
+                    ExpressionValue::Rational { numerator, denominator } => ExpressionNode::Quotient {
+                        position: position.clone(),
+                        operands: vec!(
+                            ExpressionNode::Integer { position: position.clone(), value: numerator as IntegerType },
+                            ExpressionNode::Integer { position: position.clone(), value: denominator as IntegerType },
+                        ),
+                    },
+                    ExpressionValue::NaN { reason: _ } | ExpressionValue::Infinity { sign: _ } => ExpressionNode::NaN,
+                }
+            },
+            ExpressionNode::Parenthesis { position, sign, inner } => {
+                ExpressionNode::Parenthesis { position: position.clone(), sign: sign.clone(), inner: Box::new(inner.map_numbers(f)) }
+            },
+            ExpressionNode::Sum { position, operands } => {
+                ExpressionNode::Sum { position: position.clone(), operands: operands.iter().map(|operand| operand.map_numbers(f)).collect() }
+            },
+            ExpressionNode::Difference { position, operands } => {
+                ExpressionNode::Difference { position: position.clone(), operands: operands.iter().map(|operand| operand.map_numbers(f)).collect() }
+            },
+            ExpressionNode::Product { position, operands } => {
+                ExpressionNode::Product { position: position.clone(), operands: operands.iter().map(|operand| operand.map_numbers(f)).collect() }
+            },
+            ExpressionNode::Quotient { position, operands } => {
+                ExpressionNode::Quotient { position: position.clone(), operands: operands.iter().map(|operand| operand.map_numbers(f)).collect() }
+            },
+            ExpressionNode::Power { position, base, exponent } => {
+                ExpressionNode::Power { position: position.clone(), base: Box::new(base.map_numbers(f)), exponent: Box::new(exponent.map_numbers(f)) }
+            },
+            ExpressionNode::Function { position, name, argument } => {
+                ExpressionNode::Function { position: position.clone(), name: name.clone(), argument: Box::new(argument.map_numbers(f)) }
+            },
+            ExpressionNode::Degrees { position, inner } => {
+                ExpressionNode::Degrees { position: position.clone(), inner: Box::new(inner.map_numbers(f)) }
+            },
+            ExpressionNode::ComparisonChain { position, operands, ops } => {
+                ExpressionNode::ComparisonChain { position: position.clone(), operands: operands.iter().map(|operand| operand.map_numbers(f)).collect(), ops: ops.clone() }
+            },
+            other => other.clone(),
+        }
+    }
+
+    ///
+    /// Apply `f` to every node in this tree, in place, post-order: children
+    /// are visited (and can be mutated) before their parent. Unlike
+    /// [map_numbers](ExpressionNode::map_numbers), which builds a new tree,
+    /// this mutates `self` directly, so it's cheaper for bulk rewrites
+    /// (e.g. scaling every literal) that don't need to keep the original tree.
+    ///
+    pub fn visit_mut(&mut self, f: &mut impl FnMut(&mut ExpressionNode)) {
+        match self {
+            ExpressionNode::NaN | ExpressionNode::Integer { .. } | ExpressionNode::Decimal { .. } | ExpressionNode::Variable { .. } | ExpressionNode::Constant { .. } => {},
+            ExpressionNode::Parenthesis { position: _, sign: _, inner } => inner.visit_mut(f),
+            ExpressionNode::Sum { position: _, operands }
+            | ExpressionNode::Difference { position: _, operands }
+            | ExpressionNode::Product { position: _, operands }
+            | ExpressionNode::Quotient { position: _, operands }
+            | ExpressionNode::ComparisonChain { position: _, operands, ops: _ } => {
+                for operand in operands {
+                    operand.visit_mut(f);
+                }
+            },
+            ExpressionNode::Power { position: _, base, exponent } => {
+                base.visit_mut(f);
+                exponent.visit_mut(f);
             },
+            ExpressionNode::Function { position: _, name: _, argument } => argument.visit_mut(f),
+            ExpressionNode::Degrees { position: _, inner } => inner.visit_mut(f),
         }
+        f(self);
+    }
+
+    ///
+    /// Find every `Quotient` operand (other than the first, which is the
+    /// numerator of the chain) that is a literal zero (`Integer { value: 0 }`
+    /// or `Decimal { value: 0.0 }`), returning each such operand's position.
+    ///
+    /// This is a static check, not an evaluation: it only catches a zero
+    /// written directly in the source (e.g. `1 / 0`). A divisor that merely
+    /// *evaluates* to zero, like `1 / (3 - 3)`, is not detected here; use
+    /// [Evaluate::evaluate] and check for [ExpressionValue::NaN] to catch that.
+    ///
+    pub fn division_by_zero_sites(&self) -> Vec<ParsePosition> {
+        let mut sites = Vec::new();
+        self.collect_division_by_zero_sites(&mut sites);
+        sites
+    }
+
+    fn collect_division_by_zero_sites(&self, sites: &mut Vec<ParsePosition>) {
+        match self {
+            ExpressionNode::Quotient { position: _, operands } => {
+                for operand in operands.iter().skip(1) {
+                    if is_literal_zero(operand) {
+                        sites.push(operand.position());
+                    }
+                }
+                for operand in operands {
+                    operand.collect_division_by_zero_sites(sites);
+                }
+            },
+            ExpressionNode::Sum { position: _, operands }
+            | ExpressionNode::Difference { position: _, operands }
+            | ExpressionNode::Product { position: _, operands } => {
+                for operand in operands {
+                    operand.collect_division_by_zero_sites(sites);
+                }
+            },
+            ExpressionNode::Parenthesis { position: _, sign: _, inner } => inner.collect_division_by_zero_sites(sites),
+            ExpressionNode::Power { position: _, base, exponent } => {
+                base.collect_division_by_zero_sites(sites);
+                exponent.collect_division_by_zero_sites(sites);
+            },
+            ExpressionNode::Function { position: _, name: _, argument } => argument.collect_division_by_zero_sites(sites),
+            ExpressionNode::Degrees { position: _, inner } => inner.collect_division_by_zero_sites(sites),
+            ExpressionNode::ComparisonChain { position: _, operands, ops: _ } => {
+                for operand in operands {
+                    operand.collect_division_by_zero_sites(sites);
+                }
+            },
+            ExpressionNode::NaN
+            | ExpressionNode::Integer { position: _, value: _ }
+            | ExpressionNode::Decimal { position: _, value: _ }
+            | ExpressionNode::Variable { position: _, name: _ }
+            | ExpressionNode::Constant { position: _, name: _ } => {},
+        }
+    }
+
+    ///
+    /// Every pair of distinct subtrees (by source position) in this tree that
+    /// are [structurally_eq](ExpressionNode::structurally_eq) to each other,
+    /// i.e. equal ignoring source position, paired by their spans. Useful for
+    /// spotting common-subexpression-elimination opportunities, e.g. both
+    /// `(a + b)` spans in `(a + b) * (a + b)`. Each unordered pair of nodes
+    /// from [walk_preorder](ExpressionNode::walk_preorder) is reported once;
+    /// this includes nested matches (e.g. the two `a` leaves inside the pair
+    /// of `(a + b)` subtrees are reported too), not just the largest match.
+    ///
+    pub fn duplicate_subtrees(&self) -> Vec<(ParsePosition, ParsePosition)> {
+        let nodes = self.walk_preorder();
+        let mut duplicates = Vec::new();
+        for (i, left) in nodes.iter().enumerate() {
+            for right in nodes[i + 1..].iter() {
+                if left.structurally_eq(right) {
+                    duplicates.push((left.position(), right.position()));
+                }
+            }
+        }
+        duplicates
+    }
+
+    ///
+    /// Every node in this subtree paired with its span, in depth-first
+    /// (pre-order) order: a node is yielded before its children, which are
+    /// then visited left to right. Useful for building a source map from AST
+    /// nodes back to source ranges, e.g. for editor highlighting.
+    ///
+    pub fn node_spans(&self) -> Vec<(&ExpressionNode, ParsePosition)> {
+        let mut spans = Vec::new();
+        self.collect_node_spans(&mut spans);
+        spans
+    }
+
+    fn collect_node_spans<'a>(&'a self, spans: &mut Vec<(&'a ExpressionNode, ParsePosition)>) {
+        spans.push((self, self.position()));
+        match self {
+            ExpressionNode::NaN
+            | ExpressionNode::Integer { position: _, value: _ }
+            | ExpressionNode::Decimal { position: _, value: _ }
+            | ExpressionNode::Variable { position: _, name: _ }
+            | ExpressionNode::Constant { position: _, name: _ } => {},
+            ExpressionNode::Parenthesis { position: _, sign: _, inner } => inner.collect_node_spans(spans),
+            ExpressionNode::Sum { position: _, operands }
+            | ExpressionNode::Difference { position: _, operands }
+            | ExpressionNode::Product { position: _, operands }
+            | ExpressionNode::Quotient { position: _, operands } => {
+                for operand in operands {
+                    operand.collect_node_spans(spans);
+                }
+            },
+            ExpressionNode::Power { position: _, base, exponent } => {
+                base.collect_node_spans(spans);
+                exponent.collect_node_spans(spans);
+            },
+            ExpressionNode::Function { position: _, name: _, argument } => argument.collect_node_spans(spans),
+            ExpressionNode::Degrees { position: _, inner } => inner.collect_node_spans(spans),
+            ExpressionNode::ComparisonChain { position: _, operands, ops: _ } => {
+                for operand in operands {
+                    operand.collect_node_spans(spans);
+                }
+            },
+        }
+    }
+
+    ///
+    /// This node's immediate children, in evaluation order: the operands of
+    /// `Sum`/`Difference`/`Product`/`Quotient`/`ComparisonChain`, the `base`
+    /// then `exponent` of `Power`, the `argument` of `Function`, the `inner`
+    /// of `Parenthesis`/`Degrees`, and an empty vector for the leaf variants
+    /// (`NaN`, `Integer`, `Decimal`, `Variable`, `Constant`).
+    ///
+    pub fn children(&self) -> Vec<&ExpressionNode> {
+        match self {
+            ExpressionNode::NaN
+            | ExpressionNode::Integer { position: _, value: _ }
+            | ExpressionNode::Decimal { position: _, value: _ }
+            | ExpressionNode::Variable { position: _, name: _ }
+            | ExpressionNode::Constant { position: _, name: _ } => vec!(),
+            ExpressionNode::Parenthesis { position: _, sign: _, inner } => vec!(inner.as_ref()),
+            ExpressionNode::Sum { position: _, operands }
+            | ExpressionNode::Difference { position: _, operands }
+            | ExpressionNode::Product { position: _, operands }
+            | ExpressionNode::Quotient { position: _, operands }
+            | ExpressionNode::ComparisonChain { position: _, operands, ops: _ } => operands.iter().collect(),
+            ExpressionNode::Power { position: _, base, exponent } => vec!(base.as_ref(), exponent.as_ref()),
+            ExpressionNode::Function { position: _, name: _, argument } => vec!(argument.as_ref()),
+            ExpressionNode::Degrees { position: _, inner } => vec!(inner.as_ref()),
+        }
+    }
+
+    ///
+    /// Every node in this subtree, in depth-first (pre-order) order: a node
+    /// is yielded before its [children], which are then visited left to
+    /// right. Useful for node counting, simplification passes, or anything
+    /// else that needs a uniform flat walk of the parse tree.
+    ///
+    pub fn walk_preorder(&self) -> Vec<&ExpressionNode> {
+        let mut nodes = vec!(self);
+        for child in self.children() {
+            nodes.extend(child.walk_preorder());
+        }
+        nodes
+    }
+
+    ///
+    /// The number of nodes in this subtree, including this node itself.
+    ///
+    pub fn node_count(&self) -> usize {
+        self.walk_preorder().len()
+    }
+
+    ///
+    /// The length of the longest path from this node down to a leaf, with a
+    /// leaf itself having depth 1. Useful for guarding against pathologically
+    /// nested user input before it's evaluated or otherwise walked.
+    ///
+    pub fn depth(&self) -> usize {
+        1 + self.children().iter().map(|child| child.depth()).max().unwrap_or(0)
+    }
+
+    ///
+    /// The value of this node if it's an `Integer` or `Decimal` literal,
+    /// without evaluating it; `None` for a compound node, a `Variable`, or
+    /// `NaN`, for which there's either nothing to evaluate or evaluation is
+    /// needed to get a meaningful answer.
+    ///
+    pub fn literal_value(&self) -> Option<ExpressionValue> {
+        match self {
+            ExpressionNode::Integer { position: _, value } => Some(ExpressionValue::Integer { value: *value }),
+            ExpressionNode::Decimal { position: _, value } => Some(ExpressionValue::Decimal { value: *value }),
+            _ => None,
+        }
+    }
+
+    ///
+    /// True if this node is a negative `Integer`/`Decimal` literal, or a
+    /// `Parenthesis` with a negative [SignType], the two shapes [negate]
+    /// produces when flipping the sign of a non-negative node.
+    ///
+    pub fn is_negative_literal(&self) -> bool {
+        match self {
+            ExpressionNode::Integer { position: _, value } => *value < 0,
+            ExpressionNode::Decimal { position: _, value } => *value < 0.0,
+            ExpressionNode::Parenthesis { position: _, sign: SignType::Negative, inner: _ } => true,
+            _ => false,
+        }
+    }
+
+    ///
+    /// This node with its sign flipped: a literal's value is negated in
+    /// place, a `Parenthesis`'s sign is toggled, and anything else is
+    /// wrapped in a new negatively-signed `Parenthesis` (e.g. `1 + 2` becomes
+    /// `-(1 + 2)`), since there's no other way to negate an arbitrary subtree.
+    ///
+    pub fn negate(&self) -> ExpressionNode {
+        match self {
+            ExpressionNode::Integer { position, value } => ExpressionNode::Integer { position: position.clone(), value: -value },
+            ExpressionNode::Decimal { position, value } => ExpressionNode::Decimal { position: position.clone(), value: -value },
+            ExpressionNode::Parenthesis { position, sign, inner } => {
+                ExpressionNode::Parenthesis { position: position.clone(), sign: sign.negated(), inner: inner.clone() }
+            },
+            other => ExpressionNode::Parenthesis {
+                position: other.position(),
+                sign: SignType::Negative,
+                inner: Box::new(other.clone()),
+            },
+        }
+    }
+
+    ///
+    /// Render this tree with every binary operation wrapped in explicit
+    /// parentheses, regardless of whether they're needed for precedence
+    /// (`1 + 2 * 3` becomes `(1 + (2 * 3))`); a chained n-ary node like a
+    /// three-operand `Sum` nests left-to-right (`((1 + 2) + 3)`). A `Parenthesis`
+    /// node's own sign is kept as a leading `-`. Any other node (a function call,
+    /// `Degrees`, a comparison chain) is rendered with its plain `Display` form,
+    /// so operations inside it are not wrapped. Useful as a normal form before
+    /// comparing two commutatively-equal expressions structurally.
+    ///
+    pub fn format_full_parenthesis(&self) -> String {
+        match self {
+            ExpressionNode::Sum { position: _, operands } => fold_full_parenthesis(operands, "+"),
+            ExpressionNode::Difference { position: _, operands } => fold_full_parenthesis(operands, "-"),
+            ExpressionNode::Product { position: _, operands } => fold_full_parenthesis(operands, "*"),
+            ExpressionNode::Quotient { position: _, operands } => fold_full_parenthesis(operands, "/"),
+            ExpressionNode::Power { position: _, base, exponent } => {
+                format!("({}^{})", base.format_full_parenthesis(), exponent.format_full_parenthesis())
+            },
+            ExpressionNode::Parenthesis { position: _, sign, inner } => {
+                match sign {
+                    SignType::Negative => format!("-{}", inner.format_full_parenthesis()),
+                    SignType::Positive => inner.format_full_parenthesis(),
+                }
+            },
+            other => other.to_string(),
+        }
+    }
+
+    ///
+    /// A copy of this tree with every `Parenthesis` dropped whose removal
+    /// wouldn't change how the tree evaluates: a positively-signed
+    /// `Parenthesis` whose `inner` already binds at least as tightly as
+    /// whatever position it sits in (the same test [write_operand] uses to
+    /// decide whether `Display` needs to add parentheses back). A
+    /// negatively-signed `Parenthesis` is always kept, since dropping it
+    /// would require distributing the negation through `inner` instead.
+    ///
+    /// ```
+    /// use parser::expression::parse::{parse_expression, ParseOptions};
+    /// use parser::beginning;
+    ///
+    /// let (_context, node) = parse_expression("(1 + 2) + 3", beginning(), &ParseOptions::default()).unwrap();
+    /// assert_eq!("1 + 2 + 3", node.strip_redundant_parens().to_string());
+    ///
+    /// let (_context, node) = parse_expression("(1 + 2) * 3", beginning(), &ParseOptions::default()).unwrap();
+    /// assert_eq!("(1 + 2) * 3", node.strip_redundant_parens().to_string());
+    /// ```
+    ///
+    pub fn strip_redundant_parens(&self) -> ExpressionNode {
+        self.strip_redundant_parens_at(0, false)
+    }
+
+    fn strip_redundant_parens_at(&self, outer_precedence: u8, wrap_if_equal: bool) -> ExpressionNode {
+        match self {
+            ExpressionNode::Parenthesis { position, sign: SignType::Positive, inner } => {
+                let inner_precedence = operand_precedence(inner);
+                let needs_parens = inner_precedence < outer_precedence || (wrap_if_equal && inner_precedence == outer_precedence);
+                if needs_parens {
+                    ExpressionNode::Parenthesis { position: position.clone(), sign: SignType::Positive, inner: Box::new(inner.strip_redundant_parens_at(0, false)) }
+                } else {
+                    inner.strip_redundant_parens_at(outer_precedence, wrap_if_equal)
+                }
+            },
+            ExpressionNode::Parenthesis { position, sign, inner } => {
+                ExpressionNode::Parenthesis { position: position.clone(), sign: sign.clone(), inner: Box::new(inner.strip_redundant_parens_at(0, false)) }
+            },
+            ExpressionNode::Sum { position, operands } => {
+                ExpressionNode::Sum { position: position.clone(), operands: operands.iter().map(|operand| operand.strip_redundant_parens_at(1, false)).collect() }
+            },
+            ExpressionNode::Difference { position, operands } => {
+                let operands = operands.iter().enumerate()
+                    .map(|(index, operand)| operand.strip_redundant_parens_at(1, index > 0))
+                    .collect();
+                ExpressionNode::Difference { position: position.clone(), operands }
+            },
+            ExpressionNode::Product { position, operands } => {
+                ExpressionNode::Product { position: position.clone(), operands: operands.iter().map(|operand| operand.strip_redundant_parens_at(2, false)).collect() }
+            },
+            ExpressionNode::Quotient { position, operands } => {
+                let operands = operands.iter().enumerate()
+                    .map(|(index, operand)| operand.strip_redundant_parens_at(2, index > 0))
+                    .collect();
+                ExpressionNode::Quotient { position: position.clone(), operands }
+            },
+            ExpressionNode::Power { position, base, exponent } => {
+                ExpressionNode::Power {
+                    position: position.clone(),
+                    base: Box::new(base.strip_redundant_parens_at(3, true)),
+                    exponent: Box::new(exponent.strip_redundant_parens_at(3, true)),
+                }
+            },
+            ExpressionNode::Function { position, name, argument } => {
+                ExpressionNode::Function { position: position.clone(), name: name.clone(), argument: Box::new(argument.strip_redundant_parens_at(0, false)) }
+            },
+            ExpressionNode::Degrees { position, inner } => {
+                ExpressionNode::Degrees { position: position.clone(), inner: Box::new(inner.strip_redundant_parens_at(0, false)) }
+            },
+            ExpressionNode::ComparisonChain { position, operands, ops } => {
+                ExpressionNode::ComparisonChain { position: position.clone(), operands: operands.iter().map(|operand| operand.strip_redundant_parens_at(0, false)).collect(), ops: ops.clone() }
+            },
+            other => other.clone(),
+        }
+    }
+
+    ///
+    /// This node's top-level additive terms, each paired with its original
+    /// source substring, for a "show your work" style breakdown. A `Sum`/
+    /// `Difference` root is flattened (recursively through any nested
+    /// `Sum`/`Difference` operand, since `+`/`-` chains are parsed at
+    /// different precedence levels but are both "top-level" terms); a
+    /// non-sum root returns itself as the single term.
+    ///
+    pub fn top_level_terms<'a>(&self, source: &'a str) -> Vec<(&'a str, &ExpressionNode)> {
+        let mut terms = Vec::new();
+        self.collect_top_level_terms(&mut terms);
+        terms.into_iter()
+            .map(|term| {
+                let position = term.position();
+                (&source[position.start.byte_index..position.end.byte_index], term)
+            })
+            .collect()
+    }
+
+    fn collect_top_level_terms<'a>(&'a self, terms: &mut Vec<&'a ExpressionNode>) {
+        match self {
+            ExpressionNode::Sum { position: _, operands } | ExpressionNode::Difference { position: _, operands } => {
+                for operand in operands {
+                    operand.collect_top_level_terms(terms);
+                }
+            },
+            other => terms.push(other),
+        }
+    }
+
+    ///
+    /// The number of operands (child nodes) this node directly holds:
+    /// the `Vec` length for the n-ary Sum/Difference/Product/Quotient variants,
+    /// 2 for Power (base and exponent), 1 for Parenthesis (its inner expression),
+    /// and 0 for leaf nodes (NaN, Integer, Decimal, Variable).
+    ///
+    pub fn operand_count(&self) -> usize {
+        match self {
+            ExpressionNode::NaN => 0,
+            ExpressionNode::Integer { position: _, value: _ } => 0,
+            ExpressionNode::Decimal { position: _, value: _ } => 0,
+            ExpressionNode::Variable { position: _, name: _ } => 0,
+            ExpressionNode::Constant { position: _, name: _ } => 0,
+            ExpressionNode::Parenthesis { position: _, sign: _, inner: _ } => 1,
+            ExpressionNode::Sum { position: _, operands } => operands.len(),
+            ExpressionNode::Difference { position: _, operands } => operands.len(),
+            ExpressionNode::Product { position: _, operands } => operands.len(),
+            ExpressionNode::Quotient { position: _, operands } => operands.len(),
+            ExpressionNode::Power { position: _, base: _, exponent: _ } => 2,
+            ExpressionNode::Function { position: _, name: _, argument: _ } => 1,
+            ExpressionNode::Degrees { position: _, inner: _ } => 1,
+            ExpressionNode::ComparisonChain { position: _, operands, ops: _ } => operands.len(),
+        }
+    }
+
+    ///
+    /// Check that this node (not recursively its children) has a shape
+    /// consistent with the grammar: Sum/Difference/Product/Quotient need at
+    /// least two operands to be meaningful, and Power always has exactly a
+    /// base and an exponent. This is intended to catch malformed trees built
+    /// programmatically rather than by the parser.
+    ///
+    pub fn is_well_formed(&self) -> bool {
+        match self {
+            ExpressionNode::Sum { position: _, operands: _ }
+            | ExpressionNode::Difference { position: _, operands: _ }
+            | ExpressionNode::Product { position: _, operands: _ }
+            | ExpressionNode::Quotient { position: _, operands: _ } => self.operand_count() >= 2,
+            ExpressionNode::Power { position: _, base: _, exponent: _ } => self.operand_count() == 2,
+            ExpressionNode::ComparisonChain { position: _, operands, ops } => operands.len() == ops.len() + 1,
+            _ => true,
+        }
+    }
+
+    ///
+    /// Build a copy of this node with `operand` appended as an additional
+    /// term (for `Sum`) or factor (for `Product`), re-merging the node's
+    /// span to also cover `operand`'s span. Returns a clone of `self`
+    /// unchanged for every other variant, since they don't hold a
+    /// variable-length operand list.
+    ///
+    pub fn with_operand_added(&self, operand: ExpressionNode) -> ExpressionNode {
+        match self {
+            ExpressionNode::Sum { position, operands } => {
+                let position = position.merge(&operand.position());
+                let mut operands = operands.clone();
+                operands.push(operand);
+                ExpressionNode::Sum { position, operands }
+            },
+            ExpressionNode::Product { position, operands } => {
+                let position = position.merge(&operand.position());
+                let mut operands = operands.clone();
+                operands.push(operand);
+                ExpressionNode::Product { position, operands }
+            },
+            other => other.clone(),
+        }
+    }
+
+    ///
+    /// Build a copy of this node with the operand at `index` removed,
+    /// re-computing the node's span from the remaining operands (or
+    /// leaving it unchanged if none remain). Returns a clone of `self`
+    /// unchanged for every other variant, or if `index` is out of bounds.
+    ///
+    pub fn with_operand_removed(&self, index: usize) -> ExpressionNode {
+        match self {
+            ExpressionNode::Sum { position, operands } => {
+                if index >= operands.len() {
+                    return self.clone();
+                }
+                let mut operands = operands.clone();
+                operands.remove(index);
+                let position = merged_position(position, &operands);
+                ExpressionNode::Sum { position, operands }
+            },
+            ExpressionNode::Product { position, operands } => {
+                if index >= operands.len() {
+                    return self.clone();
+                }
+                let mut operands = operands.clone();
+                operands.remove(index);
+                let position = merged_position(position, &operands);
+                ExpressionNode::Product { position, operands }
+            },
+            other => other.clone(),
+        }
+    }
+
+    ///
+    /// Compare this node to `other`, treating `Integer` and `Decimal` leaves
+    /// as interchangeable (only their presence matters, not their value) and
+    /// ignoring positions, so two expressions with the same structure but
+    /// different numbers (`1 + 2*3` and `9 + 8*7`) are considered the same shape.
+    ///
+    pub fn same_shape(&self, other: &ExpressionNode) -> bool {
+        match (self, other) {
+            (ExpressionNode::NaN, ExpressionNode::NaN) => true,
+            (ExpressionNode::Integer { position: _, value: _ } | ExpressionNode::Decimal { position: _, value: _ },
+             ExpressionNode::Integer { position: _, value: _ } | ExpressionNode::Decimal { position: _, value: _ }) => true,
+            (ExpressionNode::Variable { position: _, name: left_name },
+             ExpressionNode::Variable { position: _, name: right_name }) => left_name == right_name,
+            (ExpressionNode::Constant { position: _, name: left_name },
+             ExpressionNode::Constant { position: _, name: right_name }) => left_name == right_name,
+            (ExpressionNode::Parenthesis { position: _, sign: left_sign, inner: left_inner },
+             ExpressionNode::Parenthesis { position: _, sign: right_sign, inner: right_inner }) => {
+                left_sign == right_sign && left_inner.same_shape(right_inner)
+            },
+            (ExpressionNode::Sum { position: _, operands: left_operands },
+             ExpressionNode::Sum { position: _, operands: right_operands }) => same_shape_operands(left_operands, right_operands),
+            (ExpressionNode::Difference { position: _, operands: left_operands },
+             ExpressionNode::Difference { position: _, operands: right_operands }) => same_shape_operands(left_operands, right_operands),
+            (ExpressionNode::Product { position: _, operands: left_operands },
+             ExpressionNode::Product { position: _, operands: right_operands }) => same_shape_operands(left_operands, right_operands),
+            (ExpressionNode::Quotient { position: _, operands: left_operands },
+             ExpressionNode::Quotient { position: _, operands: right_operands }) => same_shape_operands(left_operands, right_operands),
+            (ExpressionNode::Power { position: _, base: left_base, exponent: left_exponent },
+             ExpressionNode::Power { position: _, base: right_base, exponent: right_exponent }) => {
+                left_base.same_shape(right_base) && left_exponent.same_shape(right_exponent)
+            },
+            (ExpressionNode::Function { position: _, name: left_name, argument: left_argument },
+             ExpressionNode::Function { position: _, name: right_name, argument: right_argument }) => {
+                left_name == right_name && left_argument.same_shape(right_argument)
+            },
+            (ExpressionNode::Degrees { position: _, inner: left_inner },
+             ExpressionNode::Degrees { position: _, inner: right_inner }) => left_inner.same_shape(right_inner),
+            (ExpressionNode::ComparisonChain { position: _, operands: left_operands, ops: left_ops },
+             ExpressionNode::ComparisonChain { position: _, operands: right_operands, ops: right_ops }) => {
+                left_ops == right_ops && same_shape_operands(left_operands, right_operands)
+            },
+            _ => false,
+        }
+    }
+
+    ///
+    /// Compare this node to `other` by value, ignoring source position: unlike
+    /// the derived `PartialEq` (which also compares `ParsePosition`, so two
+    /// trees parsed from different source text are never equal), this only
+    /// cares about the values and structure, operand order included.
+    ///
+    pub fn structurally_eq(&self, other: &ExpressionNode) -> bool {
+        match (self, other) {
+            (ExpressionNode::NaN, ExpressionNode::NaN) => true,
+            (ExpressionNode::Integer { position: _, value: left },
+             ExpressionNode::Integer { position: _, value: right }) => left == right,
+            (ExpressionNode::Decimal { position: _, value: left },
+             ExpressionNode::Decimal { position: _, value: right }) => left == right,
+            (ExpressionNode::Variable { position: _, name: left },
+             ExpressionNode::Variable { position: _, name: right }) => left == right,
+            (ExpressionNode::Constant { position: _, name: left },
+             ExpressionNode::Constant { position: _, name: right }) => left == right,
+            (ExpressionNode::Parenthesis { position: _, sign: left_sign, inner: left_inner },
+             ExpressionNode::Parenthesis { position: _, sign: right_sign, inner: right_inner }) => {
+                left_sign == right_sign && left_inner.structurally_eq(right_inner)
+            },
+            (ExpressionNode::Sum { position: _, operands: left_operands },
+             ExpressionNode::Sum { position: _, operands: right_operands }) => structurally_eq_operands(left_operands, right_operands),
+            (ExpressionNode::Difference { position: _, operands: left_operands },
+             ExpressionNode::Difference { position: _, operands: right_operands }) => structurally_eq_operands(left_operands, right_operands),
+            (ExpressionNode::Product { position: _, operands: left_operands },
+             ExpressionNode::Product { position: _, operands: right_operands }) => structurally_eq_operands(left_operands, right_operands),
+            (ExpressionNode::Quotient { position: _, operands: left_operands },
+             ExpressionNode::Quotient { position: _, operands: right_operands }) => structurally_eq_operands(left_operands, right_operands),
+            (ExpressionNode::Power { position: _, base: left_base, exponent: left_exponent },
+             ExpressionNode::Power { position: _, base: right_base, exponent: right_exponent }) => {
+                left_base.structurally_eq(right_base) && left_exponent.structurally_eq(right_exponent)
+            },
+            (ExpressionNode::Function { position: _, name: left_name, argument: left_argument },
+             ExpressionNode::Function { position: _, name: right_name, argument: right_argument }) => {
+                left_name == right_name && left_argument.structurally_eq(right_argument)
+            },
+            (ExpressionNode::Degrees { position: _, inner: left_inner },
+             ExpressionNode::Degrees { position: _, inner: right_inner }) => left_inner.structurally_eq(right_inner),
+            (ExpressionNode::ComparisonChain { position: _, operands: left_operands, ops: left_ops },
+             ExpressionNode::ComparisonChain { position: _, operands: right_operands, ops: right_ops }) => {
+                left_ops == right_ops && structurally_eq_operands(left_operands, right_operands)
+            },
+            _ => false,
+        }
+    }
+
+    ///
+    /// A copy of this tree with every `Sum`/`Product` operand list (the
+    /// commutative operators) sorted into a deterministic order, recursively.
+    /// `Difference`/`Quotient`/`Power` aren't commutative, so their operand
+    /// order is left alone. This is what makes [equivalent](ExpressionNode::equivalent)
+    /// able to tell `2 + 3 * 4` and `4 * 3 + 2` apart from `5 - 1` and `1 - 5`.
+    ///
+    fn canonical_commutative_order(&self) -> ExpressionNode {
+        match self {
+            ExpressionNode::Sum { position, operands } => {
+                ExpressionNode::Sum { position: position.clone(), operands: sorted_canonical_operands(operands) }
+            },
+            ExpressionNode::Product { position, operands } => {
+                ExpressionNode::Product { position: position.clone(), operands: sorted_canonical_operands(operands) }
+            },
+            ExpressionNode::Difference { position, operands } => {
+                ExpressionNode::Difference { position: position.clone(), operands: canonicalized_operands(operands) }
+            },
+            ExpressionNode::Quotient { position, operands } => {
+                ExpressionNode::Quotient { position: position.clone(), operands: canonicalized_operands(operands) }
+            },
+            ExpressionNode::Parenthesis { position, sign, inner } => {
+                ExpressionNode::Parenthesis { position: position.clone(), sign: sign.clone(), inner: Box::new(inner.canonical_commutative_order()) }
+            },
+            ExpressionNode::Power { position, base, exponent } => {
+                ExpressionNode::Power { position: position.clone(), base: Box::new(base.canonical_commutative_order()), exponent: Box::new(exponent.canonical_commutative_order()) }
+            },
+            ExpressionNode::Function { position, name, argument } => {
+                ExpressionNode::Function { position: position.clone(), name: name.clone(), argument: Box::new(argument.canonical_commutative_order()) }
+            },
+            ExpressionNode::Degrees { position, inner } => {
+                ExpressionNode::Degrees { position: position.clone(), inner: Box::new(inner.canonical_commutative_order()) }
+            },
+            ExpressionNode::ComparisonChain { position, operands, ops } => {
+                ExpressionNode::ComparisonChain { position: position.clone(), operands: canonicalized_operands(operands), ops: ops.clone() }
+            },
+            other => other.clone(),
+        }
+    }
+
+    ///
+    /// A cheap semantic-equivalence check: true if `self` and `other` are
+    /// [structurally_eq](ExpressionNode::structurally_eq) once each tree's
+    /// commutative operands are sorted into the same canonical order. This
+    /// catches reorderings of `+`/`*` operands without enumerating every
+    /// commuted form the way [generate_commuted_expressions](crate::commute::helper::generate_commuted_expressions) does, so it's cheap
+    /// enough to call freely, at the cost of missing equivalences that
+    /// need actual algebraic simplification (e.g. `x + x` vs `2 * x`).
+    ///
+    pub fn equivalent(&self, other: &ExpressionNode) -> bool {
+        self.canonical_commutative_order().structurally_eq(&other.canonical_commutative_order())
+    }
+}
+
+fn structurally_eq_operands(left: &[ExpressionNode], right: &[ExpressionNode]) -> bool {
+    left.len() == right.len() && left.iter().zip(right.iter()).all(|(l, r)| l.structurally_eq(r))
+}
+
+fn canonicalized_operands(operands: &[ExpressionNode]) -> Vec<ExpressionNode> {
+    operands.iter().map(|operand| operand.canonical_commutative_order()).collect()
+}
+
+fn sorted_canonical_operands(operands: &[ExpressionNode]) -> Vec<ExpressionNode> {
+    let mut operands = canonicalized_operands(operands);
+    operands.sort_by_key(|operand| operand.to_string());
+    operands
+}
+
+///
+/// Fold `operands` left-to-right into a fully-parenthesized chain with
+/// `operator` between each pair, e.g. `[1, 2, 3]` with `"+"` becomes
+/// `"((1 + 2) + 3)"`. Empty `operands` (possible for a degenerate n-ary
+/// node) folds to an empty string.
+///
+fn fold_full_parenthesis(operands: &[ExpressionNode], operator: &str) -> String {
+    match operands.split_first() {
+        None => String::new(),
+        Some((first, rest)) => rest.iter().fold(first.format_full_parenthesis(), |accumulated, operand| {
+            format!("({} {} {})", accumulated, operator, operand.format_full_parenthesis())
+        }),
+    }
+}
+
+///
+/// Recompute a node's span from its (possibly just-edited) `operands`,
+/// falling back to `fallback` (the node's prior span) when `operands` is
+/// empty, since there's nothing left to merge.
+///
+fn merged_position(fallback: &ParsePosition, operands: &[ExpressionNode]) -> ParsePosition {
+    let mut operands = operands.iter();
+    match operands.next() {
+        Some(first) => operands.fold(first.position(), |position, operand| position.merge(&operand.position())),
+        None => fallback.clone(),
+    }
+}
+
+///
+/// True if `left` and `right` have the same length and every corresponding
+/// pair of operands has the same shape (see [ExpressionNode::same_shape]).
+///
+fn same_shape_operands(left: &[ExpressionNode], right: &[ExpressionNode]) -> bool {
+    left.len() == right.len() && left.iter().zip(right.iter()).all(|(l, r)| l.same_shape(r))
+}
+
+impl Position for ExpressionNode {
+    fn position(&self) -> ParsePosition {
+        match self {
+            ExpressionNode::NaN => ParsePosition::default(),
+            ExpressionNode::Integer { position, value: _ } => position.clone(),
+            ExpressionNode::Decimal { position, value: _ } => position.clone(),
+            ExpressionNode::Parenthesis { position, sign: _, inner: _ } => position.clone(),
+            ExpressionNode::Sum { position, operands: _ } => position.clone(),
+            ExpressionNode::Difference { position, operands: _ } => position.clone(),
+            ExpressionNode::Product { position, operands: _ } => position.clone(),
+            ExpressionNode::Quotient { position, operands: _ } => position.clone(),
+            ExpressionNode::Power { position, base: _, exponent: _ } => position.clone(),
+            ExpressionNode::Variable { position, name: _ } => position.clone(),
+            ExpressionNode::Constant { position, name: _ } => position.clone(),
+            ExpressionNode::Function { position, name: _, argument: _ } => position.clone(),
+            ExpressionNode::Degrees { position, inner: _ } => position.clone(),
+            ExpressionNode::ComparisonChain { position, operands: _, ops: _ } => position.clone(),
+        }
+    }
+}
+
+///
+/// How tightly an operator binds, from loosest (`+`/`-`) to tightest
+/// (everything that's a single token or already self-delimiting, like a
+/// literal or a `Parenthesis`), for deciding where [Display] needs to
+/// insert parentheses around a child node.
+///
+fn operand_precedence(node: &ExpressionNode) -> u8 {
+    match node {
+        ExpressionNode::Sum { .. } | ExpressionNode::Difference { .. } => 1,
+        ExpressionNode::Product { .. } | ExpressionNode::Quotient { .. } => 2,
+        ExpressionNode::Power { .. } => 3,
+        _ => 4,
+    }
+}
+
+///
+/// Write `operand` as a child of an operator at `outer_precedence`
+/// (see [operand_precedence]), wrapping it in parentheses if its own
+/// precedence is lower, or (when `wrap_if_equal` is set, for a
+/// non-commutative outer operator like `-` or `/`) the same.
+///
+fn write_operand(f: &mut std::fmt::Formatter<'_>, operand: &ExpressionNode, outer_precedence: u8, wrap_if_equal: bool) -> std::fmt::Result {
+    let inner_precedence = operand_precedence(operand);
+    if inner_precedence < outer_precedence || (wrap_if_equal && inner_precedence == outer_precedence) {
+        write(f, format_args!("({})", operand))
+    } else {
+        write(f, format_args!("{}", operand))
+    }
+}
+
+impl Display for ExpressionNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpressionNode::NaN => f.write_str(&ExpressionValue::NaN { reason: None }.to_string()),
+            ExpressionNode::Integer { position: _, value } => f.write_fmt(format_args!("{}", &value)),
+            ExpressionNode::Decimal { position: _, value } => f.write_fmt(format_args!("{}", &value)),
+            ExpressionNode::Parenthesis { position: _, sign, inner } => {
+                match sign {
+                    SignType::Negative => f.write_fmt(format_args!("-({})", &inner)),
+                    SignType::Positive => f.write_fmt(format_args!("({})", &inner)),
+                }
+            },
+            ExpressionNode::Sum { position: _, operands } => {
+                if let Some((first, rest)) = operands.split_first() {
+                    write_operand(f, first, 1, false)?;
+                    for operand in rest {
+                        f.write_str(" + ")?;
+                        write_operand(f, operand, 1, false)?;
+                    }
+                }
+                Ok(())
+            },
+            ExpressionNode::Difference { position: _, operands } => {
+                if let Some((first, rest)) = operands.split_first() {
+                    write_operand(f, first, 1, false)?;
+                    for operand in rest {
+                        f.write_str(" - ")?;
+                        write_operand(f, operand, 1, true)?;
+                    }
+                }
+                Ok(())
+            },
+            ExpressionNode::Product { position: _, operands } => {
+                if let Some((first, rest)) = operands.split_first() {
+                    write_operand(f, first, 2, false)?;
+                    for operand in rest {
+                        f.write_str(" * ")?;
+                        write_operand(f, operand, 2, false)?;
+                    }
+                }
+                Ok(())
+            },
+            ExpressionNode::Quotient { position: _, operands } => {
+                if let Some((first, rest)) = operands.split_first() {
+                    write_operand(f, first, 2, false)?;
+                    for operand in rest {
+                        f.write_str(" / ")?;
+                        write_operand(f, operand, 2, true)?;
+                    }
+                }
+                Ok(())
+            },
+            ExpressionNode::Power { position: _, base, exponent } => {
+                write_operand(f, base, 3, true)?;
+                f.write_str("^")?;
+                write_operand(f, exponent, 3, true)
+            },
+            ExpressionNode::Variable { position: _, name } => f.write_str(name),
+            ExpressionNode::Constant { position: _, name } => f.write_str(name),
+            ExpressionNode::Function { position: _, name, argument } => {
+                f.write_fmt(format_args!("{}({})", name, argument))
+            },
+            ExpressionNode::Degrees { position: _, inner } => {
+                f.write_fmt(format_args!("{}°", inner))
+            },
+            ExpressionNode::ComparisonChain { position: _, operands, ops } => {
+                if !operands.is_empty() {
+                    write(f, format_args!("{}", &operands[0]))?;
+                    for (op, operand) in ops.iter().zip(operands[1..].iter()) {
+                        write(f, format_args!(" {} {}", op.symbol(), operand))?;
+                    }
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl ExpressionNode {
+    ///
+    /// Build an `Integer` node at `position`, rejecting a `position` whose
+    /// `start` comes after its `end` (see [ParsePosition::is_valid]); unlike
+    /// the struct literal `ExpressionNode::Integer { .. }` used internally
+    /// by the parser (which always builds a valid span itself), this is for
+    /// callers building nodes from a `position` they didn't compute themselves.
+    ///
+    pub fn integer(value: IntegerType, position: ParsePosition) -> Result<ExpressionNode, PositionError> {
+        if position.is_valid() {
+            Ok(ExpressionNode::Integer { position, value })
+        } else {
+            Err(PositionError { position })
+        }
+    }
+
+    /// Build a `Decimal` node at `position`; see [ExpressionNode::integer].
+    pub fn decimal(value: DecimalType, position: ParsePosition) -> Result<ExpressionNode, PositionError> {
+        if position.is_valid() {
+            Ok(ExpressionNode::Decimal { position, value })
+        } else {
+            Err(PositionError { position })
+        }
+    }
+
+    /// Build a `Variable` node at `position`; see [ExpressionNode::integer].
+    pub fn variable(name: String, position: ParsePosition) -> Result<ExpressionNode, PositionError> {
+        if position.is_valid() {
+            Ok(ExpressionNode::Variable { position, name })
+        } else {
+            Err(PositionError { position })
+        }
+    }
+}
+
+impl From<IntegerType> for ExpressionNode {
+    fn from(value: IntegerType) -> ExpressionNode {
+        ExpressionNode::Integer { position: ParsePosition::default(), value }
+    }
+}
+
+impl From<DecimalType> for ExpressionNode {
+    fn from(value: DecimalType) -> ExpressionNode {
+        ExpressionNode::Decimal { position: ParsePosition::default(), value }
+    }
+}
+
+///
+/// Build a `Sum` node with zeroed positions, for programmatic tree
+/// construction (e.g. `ExpressionNode::from(1) + ExpressionNode::from(2)`);
+/// the source text these nodes never had doesn't matter for evaluation or
+/// [structurally_eq](ExpressionNode::structurally_eq).
+///
+impl std::ops::Add for ExpressionNode {
+    type Output = ExpressionNode;
+
+    fn add(self, rhs: ExpressionNode) -> ExpressionNode {
+        ExpressionNode::Sum { position: ParsePosition::default(), operands: vec![self, rhs] }
+    }
+}
+
+/// Build a `Difference` node with zeroed positions; see `Add` above.
+impl std::ops::Sub for ExpressionNode {
+    type Output = ExpressionNode;
+
+    fn sub(self, rhs: ExpressionNode) -> ExpressionNode {
+        ExpressionNode::Difference { position: ParsePosition::default(), operands: vec![self, rhs] }
+    }
+}
+
+/// Build a `Product` node with zeroed positions; see `Add` above.
+impl std::ops::Mul for ExpressionNode {
+    type Output = ExpressionNode;
+
+    fn mul(self, rhs: ExpressionNode) -> ExpressionNode {
+        ExpressionNode::Product { position: ParsePosition::default(), operands: vec![self, rhs] }
+    }
+}
+
+/// Build a `Quotient` node with zeroed positions; see `Add` above.
+impl std::ops::Div for ExpressionNode {
+    type Output = ExpressionNode;
+
+    fn div(self, rhs: ExpressionNode) -> ExpressionNode {
+        ExpressionNode::Quotient { position: ParsePosition::default(), operands: vec![self, rhs] }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::expression::parse::{parse_expression, ParseOptions};
+    use crate::expression::position::{ParsePosition, PositionError};
+    use crate::expression::value::{ExpressionValue, Rounding};
+    use crate::expression::error::{ParsingError, EvaluationError};
+    use crate::scan::context::{beginning, ScanPosition};
+
+    use super::ExpressionNode;
+    use super::EvalOptions;
+    use super::Evaluate;
+    use super::Position;
+    use super::evaluate_all;
+    use super::count_commuted_forms;
+
+    #[test]
+    fn test_evaluate_empty_sum_is_additive_identity() {
+        let node = ExpressionNode::Sum { position: ParsePosition::default(), operands: vec!() };
+        assert_eq!(ExpressionValue::Integer { value: 0 }, node.evaluate());
+    }
+
+    #[test]
+    fn test_evaluate_empty_product_is_multiplicative_identity() {
+        let node = ExpressionNode::Product { position: ParsePosition::default(), operands: vec!() };
+        assert_eq!(ExpressionValue::Integer { value: 1 }, node.evaluate());
+    }
+
+    #[test]
+    fn test_comparison_chain_true_when_all_links_hold() {
+        let (_context, node) = parse_expression("1 < 2 < 3", beginning(), &ParseOptions::default()).unwrap();
+        assert!(matches!(node, ExpressionNode::ComparisonChain { .. }));
+        assert_eq!(ExpressionValue::Integer { value: 1 }, node.evaluate());
+    }
+
+    #[test]
+    fn test_comparison_chain_false_when_one_link_fails() {
+        let (_context, node) = parse_expression("1 < 5 < 3", beginning(), &ParseOptions::default()).unwrap();
+        assert!(matches!(node, ExpressionNode::ComparisonChain { .. }));
+        assert_eq!(ExpressionValue::Integer { value: 0 }, node.evaluate());
+    }
+
+    #[test]
+    fn test_comparison_chain_evaluates_shared_middle_operand_once() {
+        let (_context, node) = parse_expression("1 < 2 < 3", beginning(), &ParseOptions::default()).unwrap();
+        let middle = match &node {
+            ExpressionNode::ComparisonChain { operands, .. } => operands[1].clone(),
+            other => panic!("expected a ComparisonChain, got {:?}", other),
+        };
+
+        let mut middle_evaluations = 0;
+        node.evaluate_with_hook(&mut |visited, _value| {
+            if *visited == middle {
+                middle_evaluations += 1;
+            }
+        });
+
+        assert_eq!(1, middle_evaluations);
+    }
+
+    #[test]
+    fn test_operand_count_and_well_formed_sum() {
+        let (_context, node) = parse_expression("1+2+3", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(3, node.operand_count());
+        assert!(node.is_well_formed());
+    }
+
+    #[test]
+    fn test_is_well_formed_false_for_single_operand_sum() {
+        let node = ExpressionNode::Sum {
+            position: ParsePosition::default(),
+            operands: vec!(ExpressionNode::Integer { position: ParsePosition::default(), value: 1 }),
+        };
+        assert_eq!(1, node.operand_count());
+        assert!(!node.is_well_formed());
+    }
+
+    #[test]
+    fn test_with_operand_added_appends_term_and_grows_span() {
+        let (_context, node) = parse_expression("1+2+3", beginning(), &ParseOptions::default()).unwrap();
+        let four = ExpressionNode::Integer { position: ParsePosition::new(&ScanPosition::new(10, 10, 0, 0, 0), &ScanPosition::new(11, 11, 0, 0, 0)), value: 4 };
+
+        let added = node.with_operand_added(four);
+
+        assert_eq!(4, added.operand_count());
+        assert_eq!(ExpressionValue::Integer { value: 10 }, added.evaluate());
+        assert_eq!(node.position().start, added.position().start);
+        assert_eq!(11, added.position().end.byte_index);
+    }
+
+    #[test]
+    fn test_with_operand_removed_drops_middle_operand() {
+        let (_context, node) = parse_expression("1+2+3", beginning(), &ParseOptions::default()).unwrap();
+
+        let removed = node.with_operand_removed(1);
+
+        assert_eq!(2, removed.operand_count());
+        assert_eq!(ExpressionValue::Integer { value: 4 }, removed.evaluate());
+    }
+
+    #[test]
+    fn test_operand_added_and_removed_are_unchanged_for_other_variants() {
+        let node = ExpressionNode::Integer { position: ParsePosition::default(), value: 1 };
+
+        assert_eq!(node, node.with_operand_added(ExpressionNode::Integer { position: ParsePosition::default(), value: 2 }));
+        assert_eq!(node, node.with_operand_removed(0));
+    }
+
+    #[test]
+    fn test_count_commuted_forms_flat_sum() {
+        let (_context, node) = parse_expression("1+2+3", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(6, count_commuted_forms(&node));
+    }
+
+    #[test]
+    fn test_count_commuted_forms_nested_product_of_sums() {
+        let (_context, node) = parse_expression("(1+2) * (3+4)", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(8, count_commuted_forms(&node));
+    }
+
+    #[test]
+    fn test_magnitude_bound_power_overflow_risk() {
+        let (_context, node) = parse_expression("2^30", beginning(), &ParseOptions::default()).unwrap();
+        let bound = node.magnitude_bound().unwrap();
+        assert!(bound > 1e9, "expected bound over 1e9, got {}", bound);
+    }
+
+    #[test]
+    fn test_magnitude_bound_sum() {
+        let (_context, node) = parse_expression("1 + 1", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(Some(2.0), node.magnitude_bound());
+    }
+
+    #[test]
+    fn test_map_numbers_doubles_every_literal() {
+        let (_context, node) = parse_expression("1 + 2 * 3", beginning(), &ParseOptions::default()).unwrap();
+        let (_context, expected) = parse_expression("2 + 4 * 6", beginning(), &ParseOptions::default()).unwrap();
+
+        let doubled = node.map_numbers(&|value| match value {
+            ExpressionValue::Integer { value } => ExpressionValue::Integer { value: value * 2 },
+            ExpressionValue::Decimal { value } => ExpressionValue::Decimal { value: value * 2.0 },
+            ExpressionValue::Rational { numerator, denominator } => ExpressionValue::Rational { numerator: numerator * 2, denominator },
+            ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+            ExpressionValue::Infinity { sign } => ExpressionValue::Infinity { sign },
+        });
+
+        assert_eq!(expected.evaluate(), doubled.evaluate());
+    }
+
+    #[test]
+    fn test_visit_mut_doubles_every_integer_in_place() {
+        let (_context, mut node) = parse_expression("1 + 2", beginning(), &ParseOptions::default()).unwrap();
+
+        node.visit_mut(&mut |n| {
+            if let ExpressionNode::Integer { position: _, value } = n {
+                *value *= 2;
+            }
+        });
+
+        assert_eq!(ExpressionValue::Integer { value: 6 }, node.evaluate());
+    }
+
+    #[test]
+    fn test_binarize_difference_is_left_associative_and_nested() {
+        let (_context, node) = parse_expression("10 - 3 - 2", beginning(), &ParseOptions::default()).unwrap();
+        let binarized = node.binarize();
+
+        assert_eq!(
+            ExpressionNode::Difference {
+                position: ParsePosition::default(),
+                operands: vec!(
+                    ExpressionNode::Difference {
+                        position: ParsePosition::default(),
+                        operands: vec!(
+                            ExpressionNode::Integer { position: ParsePosition::default(), value: 10 },
+                            ExpressionNode::Integer { position: ParsePosition::default(), value: 3 },
+                        ),
+                    },
+                    ExpressionNode::Integer { position: ParsePosition::default(), value: 2 },
+                ),
+            },
+            strip_positions(&binarized)
+        );
+        assert_eq!(ExpressionValue::Integer { value: 5 }, binarized.evaluate());
+    }
+
+    // positions carry real `ScanPosition`s from parsing, but `binarize`'s
+    // expected shape is easiest to express with default positions, so zero
+    // them out before comparing.
+    fn strip_positions(node: &ExpressionNode) -> ExpressionNode {
+        match node {
+            ExpressionNode::Difference { position: _, operands } => {
+                ExpressionNode::Difference { position: ParsePosition::default(), operands: operands.iter().map(strip_positions).collect() }
+            },
+            ExpressionNode::Integer { position: _, value } => ExpressionNode::Integer { position: ParsePosition::default(), value: *value },
+            other => other.clone(),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_iterative_deeply_nested_parenthesis_does_not_overflow() {
+        let mut node = ExpressionNode::Integer { position: ParsePosition::default(), value: 1 };
+        for _ in 0..5000 {
+            node = ExpressionNode::Parenthesis {
+                position: ParsePosition::default(),
+                sign: crate::expression::value::SignType::Positive,
+                inner: Box::new(node),
+            };
+        }
+
+        assert_eq!(ExpressionValue::Integer { value: 1 }, node.evaluate_iterative());
+    }
+
+    #[test]
+    fn test_evaluate_iterative_matches_evaluate() {
+        let (_context, node) = parse_expression("(((10 + 5) * -6) - -20 / -2 * 3 + -((5*2)^2) - (-5 * -2 * 5))", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(node.evaluate(), node.evaluate_iterative());
+    }
+
+    #[test]
+    fn test_evaluate_with_options_short_circuits_zero_product_suppressing_nan() {
+        let (_context, node) = parse_expression("0 * (1 / 0)", beginning(), &ParseOptions::default()).unwrap();
+        let options = EvalOptions { short_circuit_zero_product: true, ..EvalOptions::default() };
+
+        assert_eq!(ExpressionValue::Integer { value: 0 }, node.evaluate_with_options(&options));
+    }
+
+    #[test]
+    fn test_evaluate_with_options_without_short_circuit_yields_nan() {
+        let (_context, node) = parse_expression("0 * (1 / 0)", beginning(), &ParseOptions::default()).unwrap();
+        let options = EvalOptions { short_circuit_zero_product: false, ..EvalOptions::default() };
+
+        assert!(matches!(node.evaluate_with_options(&options), ExpressionValue::NaN { .. }));
+    }
+
+    #[test]
+    fn test_evaluate_with_options_division_rounding_defaults_to_truncate() {
+        let (_context, node) = parse_expression("-7 / 2", beginning(), &ParseOptions::default()).unwrap();
+        let options = EvalOptions::default();
+
+        assert_eq!(ExpressionValue::Integer { value: -3 }, node.evaluate_with_options(&options));
+    }
+
+    #[test]
+    fn test_evaluate_with_options_division_rounding_floor() {
+        let (_context, node) = parse_expression("-7 / 2", beginning(), &ParseOptions::default()).unwrap();
+        let options = EvalOptions { division_rounding: Rounding::Floor, ..EvalOptions::default() };
+
+        assert_eq!(ExpressionValue::Integer { value: -4 }, node.evaluate_with_options(&options));
+    }
+
+    #[test]
+    fn test_try_evaluate_divide_by_zero_errors_with_the_divide_by_zero_reason() {
+        let (_context, node) = parse_expression("1 / 0", beginning(), &ParseOptions::default()).unwrap();
+        let error = node.try_evaluate(&HashMap::new()).unwrap_err();
+        assert!(matches!(error, ParsingError::Evaluation(_, EvaluationError::DivideByZero)));
+    }
+
+    #[test]
+    fn test_try_evaluate_sqrt_of_negative_number_errors_with_domain_error() {
+        let (_context, node) = parse_expression("sqrt(-1)", beginning(), &ParseOptions::default()).unwrap();
+        let error = node.try_evaluate(&HashMap::new()).unwrap_err();
+        assert!(matches!(error, ParsingError::Evaluation(_, EvaluationError::DomainError { .. })));
+    }
+
+    #[test]
+    fn test_try_evaluate_unbound_variable_errors_with_unbound_variable() {
+        let (_context, node) = parse_expression("x + 1", beginning(), &ParseOptions::default()).unwrap();
+        let error = node.try_evaluate(&HashMap::new()).unwrap_err();
+        assert!(matches!(error, ParsingError::Evaluation(_, EvaluationError::UnboundVariable { ref name }) if name == "x"));
+    }
+
+    #[test]
+    fn test_try_evaluate_succeeds_with_bound_variable() {
+        let (_context, node) = parse_expression("x + 1", beginning(), &ParseOptions::default()).unwrap();
+        let mut bindings = HashMap::new();
+        bindings.insert("x".to_string(), ExpressionValue::Integer { value: 4 });
+        assert_eq!(ExpressionValue::Integer { value: 5 }, node.try_evaluate(&bindings).unwrap());
+    }
+
+    #[test]
+    fn test_evaluate_with_hook_counts_nodes_and_yields_correct_root_value() {
+        let (_context, node) = parse_expression("(1+2)*3", beginning(), &ParseOptions::default()).unwrap();
+
+        let mut call_count = 0;
+        let mut last_value = ExpressionValue::NaN { reason: None };
+        let result = node.evaluate_with_hook(&mut |_node, value| {
+            call_count += 1;
+            last_value = value.clone();
+        });
+
+        // 1, 2, the Sum (1+2), the Parenthesis, 3, and the Product: 6 nodes
+        assert_eq!(6, call_count);
+        assert_eq!(ExpressionValue::Integer { value: 9 }, result);
+        assert_eq!(result, last_value);
+    }
+
+    #[test]
+    fn test_evaluate_flagged_pure_integer_arithmetic_is_not_approximate() {
+        let (_context, node) = parse_expression("2 + 3", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!((ExpressionValue::Integer { value: 5 }, false), node.evaluate_flagged());
+    }
+
+    #[test]
+    fn test_evaluate_flagged_decimal_operand_is_approximate() {
+        let (_context, node) = parse_expression("2 + 3.0", beginning(), &ParseOptions::default()).unwrap();
+        let (value, approximate) = node.evaluate_flagged();
+        assert_eq!(ExpressionValue::Decimal { value: 5.0 }, value);
+        assert!(approximate);
+    }
+
+    #[test]
+    fn test_evaluate_flagged_function_call_is_approximate() {
+        let (_context, node) = parse_expression("√2", beginning(), &ParseOptions::default()).unwrap();
+        let (_value, approximate) = node.evaluate_flagged();
+        assert!(approximate);
+    }
+
+    #[test]
+    fn test_duplicate_subtrees_finds_repeated_sum_of_variables() {
+        let (_context, node) = parse_expression("(a + b) * (a + b)", beginning(), &ParseOptions::default()).unwrap();
+        let duplicates = node.duplicate_subtrees();
+
+        let sums: Vec<&ExpressionNode> = node.walk_preorder().into_iter()
+            .filter(|node| matches!(node, ExpressionNode::Sum { .. }))
+            .collect();
+        assert_eq!(2, sums.len());
+
+        assert!(duplicates.iter().any(|(left, right)| {
+            *left == sums[0].position() && *right == sums[1].position()
+        }));
+    }
+
+    #[test]
+    fn test_duplicate_subtrees_finds_repeated_sum_of_literals() {
+        let (_context, node) = parse_expression("(1+2) + (1+2)", beginning(), &ParseOptions::default()).unwrap();
+        let duplicates = node.duplicate_subtrees();
+
+        // the outer `+` is itself a Sum, plus the two inner `(1+2)` sums: 3 total
+        let sums: Vec<&ExpressionNode> = node.walk_preorder().into_iter()
+            .filter(|node| matches!(node, ExpressionNode::Sum { .. }))
+            .collect();
+        assert_eq!(3, sums.len());
+
+        assert!(duplicates.iter().any(|(left, right)| {
+            *left == sums[1].position() && *right == sums[2].position()
+        }));
+    }
+
+    #[test]
+    fn test_duplicate_subtrees_is_empty_when_no_subtree_repeats() {
+        let (_context, node) = parse_expression("1 + 2", beginning(), &ParseOptions::default()).unwrap();
+        assert!(node.duplicate_subtrees().is_empty());
+    }
+
+    #[test]
+    fn test_node_spans_depth_first_count_and_root_span() {
+        let (_context, node) = parse_expression("(1+2)*3", beginning(), &ParseOptions::default()).unwrap();
+        let spans = node.node_spans();
+
+        // the Product, the Parenthesis, the Sum (1+2), 1, 2, and 3: 6 nodes
+        assert_eq!(6, spans.len());
+        assert_eq!(node.position(), spans[0].1);
+        assert_eq!(0, spans[0].1.start.byte_index);
+        assert_eq!(7, spans[0].1.end.byte_index);
+    }
+
+    #[test]
+    fn test_node_spans_leaf_spans_cover_individual_numbers() {
+        let (_context, node) = parse_expression("(1+2)*3", beginning(), &ParseOptions::default()).unwrap();
+        let spans = node.node_spans();
+
+        let leaf_spans: Vec<(usize, usize)> = spans.iter()
+            .filter(|(node, _)| matches!(node, ExpressionNode::Integer { position: _, value: _ }))
+            .map(|(_, position)| (position.start.byte_index, position.end.byte_index))
+            .collect();
+
+        assert_eq!(vec!((1, 2), (3, 4), (6, 7)), leaf_spans);
+    }
+
+    #[test]
+    fn test_walk_preorder_node_kinds_for_parenthesized_product() {
+        let (_context, node) = parse_expression("(1 + 2) * 3", beginning(), &ParseOptions::default()).unwrap();
+        let kinds: Vec<&str> = node.walk_preorder().iter().map(|node| match node {
+            ExpressionNode::Product { .. } => "Product",
+            ExpressionNode::Parenthesis { .. } => "Parenthesis",
+            ExpressionNode::Sum { .. } => "Sum",
+            ExpressionNode::Integer { .. } => "Integer",
+            other => panic!("unexpected node kind in (1 + 2) * 3: {:?}", other),
+        }).collect();
+
+        // preorder: Product, then its operands left to right: Parenthesis
+        // (then its inner Sum, then the Sum's operands 1 and 2), then 3
+        assert_eq!(vec!("Product", "Parenthesis", "Sum", "Integer", "Integer", "Integer"), kinds);
+    }
+
+    #[test]
+    fn test_children_of_leaf_nodes_is_empty() {
+        let (_context, node) = parse_expression("42", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(0, node.children().len());
+    }
+
+    #[test]
+    fn test_children_of_power_is_base_then_exponent() {
+        let (_context, node) = parse_expression("2^3", beginning(), &ParseOptions::default()).unwrap();
+        let children = node.children();
+
+        assert_eq!(2, children.len());
+        assert_eq!(Some(ExpressionValue::Integer { value: 2 }), children[0].literal_value());
+        assert_eq!(Some(ExpressionValue::Integer { value: 3 }), children[1].literal_value());
+    }
+
+    #[test]
+    fn test_fold_constants_except_folds_constant_subtrees_only() {
+        let (_context, node) = parse_expression("2 * 3 + x * (4 + 1)", beginning(), &ParseOptions::default()).unwrap();
+        let folded = node.fold_constants_except("x");
+
+        let (_context, expected) = parse_expression("6 + x * 5", beginning(), &ParseOptions::default()).unwrap();
+        assert!(folded.structurally_eq(&expected));
+    }
+
+    #[test]
+    fn test_fold_constants_except_evaluates_identically_to_unfolded() {
+        let (_context, node) = parse_expression("2 * 3 + x * (4 + 1)", beginning(), &ParseOptions::default()).unwrap();
+        let folded = node.fold_constants_except("x");
+
+        for x in [0.0, 1.0, -2.5, 10.0] {
+            assert_eq!(node.evaluate_table("x", &[x]), folded.evaluate_table("x", &[x]));
+        }
+    }
+
+    #[test]
+    fn test_depth_and_node_count_of_leaf() {
+        let (_context, node) = parse_expression("2", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(1, node.depth());
+        assert_eq!(1, node.node_count());
+    }
+
+    #[test]
+    fn test_depth_and_node_count_of_power() {
+        let (_context, node) = parse_expression("2^3", beginning(), &ParseOptions::default()).unwrap();
+        // Power, base (2), exponent (3): depth 2, 3 nodes
+        assert_eq!(2, node.depth());
+        assert_eq!(3, node.node_count());
+    }
+
+    #[test]
+    fn test_depth_and_node_count_of_nested_products_and_differences() {
+        let (_context, node) = parse_expression("(1 + 2) * (3 - 4)", beginning(), &ParseOptions::default()).unwrap();
+        // Product -> { Parenthesis -> Sum -> (1, 2), Parenthesis -> Difference -> (3, 4) }
+        assert_eq!(4, node.depth());
+        assert_eq!(9, node.node_count());
+    }
+
+    #[test]
+    fn test_literal_value_integer() {
+        let (_context, node) = parse_expression("42", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(Some(ExpressionValue::Integer { value: 42 }), node.literal_value());
+    }
+
+    #[test]
+    fn test_literal_value_decimal() {
+        let (_context, node) = parse_expression("3.14", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(Some(ExpressionValue::Decimal { value: 3.14 }), node.literal_value());
+    }
+
+    #[test]
+    fn test_literal_value_none_for_compound_node() {
+        let (_context, node) = parse_expression("1+2", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(None, node.literal_value());
+    }
+
+    #[test]
+    fn test_negate_flips_a_positive_literal() {
+        let (_context, node) = parse_expression("5", beginning(), &ParseOptions::default()).unwrap();
+        assert!(!node.is_negative_literal());
+
+        let negated = node.negate();
+        assert!(negated.is_negative_literal());
+        assert_eq!(ExpressionValue::Integer { value: -5 }, negated.evaluate());
+    }
+
+    #[test]
+    fn test_negate_flips_a_negative_literal_back_to_positive() {
+        let (_context, node) = parse_expression("-5", beginning(), &ParseOptions::default()).unwrap();
+        assert!(node.is_negative_literal());
+
+        let negated = node.negate();
+        assert!(!negated.is_negative_literal());
+        assert_eq!(ExpressionValue::Integer { value: 5 }, negated.evaluate());
+    }
+
+    #[test]
+    fn test_negate_wraps_a_compound_node_in_a_negative_parenthesis() {
+        let (_context, node) = parse_expression("(1+2)", beginning(), &ParseOptions::default()).unwrap();
+        assert!(!node.is_negative_literal());
+
+        let negated = node.negate();
+        assert!(negated.is_negative_literal());
+        assert_eq!("-(1 + 2)", negated.to_string());
+        assert_eq!(ExpressionValue::Integer { value: -3 }, negated.evaluate());
+    }
+
+    #[test]
+    fn test_negate_wraps_a_bare_sum_with_no_surrounding_parenthesis() {
+        let node = ExpressionNode::from(1) + ExpressionNode::from(2);
+
+        let negated = node.negate();
+        assert_eq!("-(1 + 2)", negated.to_string());
+        assert_eq!(ExpressionValue::Integer { value: -3 }, negated.evaluate());
+    }
+
+    #[test]
+    fn test_integer_constructor_accepts_a_valid_span() {
+        let position = ParsePosition::new(&ScanPosition::new(0, 0, 0, 0, 0), &ScanPosition::new(3, 3, 0, 0, 0));
+        let node = ExpressionNode::integer(42, position.clone()).unwrap();
+        assert_eq!(ExpressionNode::Integer { position, value: 42 }, node);
+    }
+
+    #[test]
+    fn test_integer_constructor_rejects_a_reversed_span() {
+        let position = ParsePosition::new(&ScanPosition::new(3, 3, 0, 0, 0), &ScanPosition::new(0, 0, 0, 0, 0));
+        match ExpressionNode::integer(42, position.clone()) {
+            Err(PositionError { position: err_position }) => assert_eq!(position, err_position),
+            other => panic!("expected a PositionError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decimal_and_variable_constructors_reject_a_reversed_span() {
+        let position = ParsePosition::new(&ScanPosition::new(3, 3, 0, 0, 0), &ScanPosition::new(0, 0, 0, 0, 0));
+        assert!(ExpressionNode::decimal(1.5, position.clone()).is_err());
+        assert!(ExpressionNode::variable("x".to_string(), position).is_err());
+    }
+
+    #[test]
+    fn test_format_full_parenthesis_wraps_every_binary_operation() {
+        let (_context, node) = parse_expression("1 + 2 * 3", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!("(1 + (2 * 3))", node.format_full_parenthesis());
+    }
+
+    #[test]
+    fn test_format_full_parenthesis_nests_three_operand_sum_left_to_right() {
+        let (_context, node) = parse_expression("1 + 2 + 3", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!("((1 + 2) + 3)", node.format_full_parenthesis());
+    }
+
+    #[test]
+    fn test_format_full_parenthesis_keeps_negative_parenthesis_sign() {
+        let (_context, node) = parse_expression("-(1 + 2)", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!("-(1 + 2)", node.format_full_parenthesis());
+    }
+
+    #[test]
+    fn test_format_full_parenthesis_covers_nested_power() {
+        let built = ExpressionNode::Power {
+            position: ParsePosition::default(),
+            base: Box::new(ExpressionNode::Power {
+                position: ParsePosition::default(),
+                base: Box::new(ExpressionNode::from(2)),
+                exponent: Box::new(ExpressionNode::from(3)),
+            }),
+            exponent: Box::new(ExpressionNode::from(4)),
+        };
+        assert_eq!("((2^3)^4)", built.format_full_parenthesis());
+    }
+
+    #[test]
+    fn test_strip_redundant_parens_removes_parens_around_same_precedence_operand() {
+        let (_context, node) = parse_expression("(1 + 2) + 3", beginning(), &ParseOptions::default()).unwrap();
+        let stripped = node.strip_redundant_parens();
+
+        assert_eq!("1 + 2 + 3", stripped.to_string());
+        assert_eq!(node.evaluate(), stripped.evaluate());
+    }
+
+    #[test]
+    fn test_strip_redundant_parens_keeps_parens_required_by_precedence() {
+        let (_context, node) = parse_expression("(1 + 2) * 3", beginning(), &ParseOptions::default()).unwrap();
+        let stripped = node.strip_redundant_parens();
+
+        assert_eq!("(1 + 2) * 3", stripped.to_string());
+        assert_eq!(node.evaluate(), stripped.evaluate());
+    }
+
+    #[test]
+    fn test_strip_redundant_parens_keeps_negative_sign() {
+        let (_context, node) = parse_expression("-(1 + 2) + 3", beginning(), &ParseOptions::default()).unwrap();
+        let stripped = node.strip_redundant_parens();
+
+        assert_eq!("-(1 + 2) + 3", stripped.to_string());
+        assert_eq!(node.evaluate(), stripped.evaluate());
+    }
+
+    #[test]
+    fn test_top_level_terms_flattens_sum_and_nested_difference() {
+        let source = "1 + 2*3 - 4";
+        let (_context, node) = parse_expression(source, beginning(), &ParseOptions::default()).unwrap();
+
+        let terms: Vec<&str> = node.top_level_terms(source).into_iter().map(|(text, _node)| text).collect();
+        assert_eq!(vec!["1", "2*3", "4"], terms);
+    }
+
+    #[test]
+    fn test_top_level_terms_non_sum_root_is_single_term() {
+        let source = "2*3";
+        let (_context, node) = parse_expression(source, beginning(), &ParseOptions::default()).unwrap();
+
+        let terms: Vec<&str> = node.top_level_terms(source).into_iter().map(|(text, _node)| text).collect();
+        assert_eq!(vec!["2*3"], terms);
+    }
+
+    #[test]
+    fn test_division_by_zero_sites_finds_literal_but_not_computed_zero() {
+        let s = "1 / 0 + 2 / (3 - 3)";
+        let (_context, node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        let sites = node.division_by_zero_sites();
+
+        // only the literal `0` in `1 / 0` is reported; `3 - 3` evaluates to
+        // zero but is not a literal zero, so it is not detected statically.
+        assert_eq!(1, sites.len());
+        assert_eq!("0", &s[sites[0].start.byte_index..sites[0].end.byte_index]);
+    }
+
+    #[test]
+    fn test_same_shape_ignores_literal_values() {
+        let (_context, left) = parse_expression("1 + 2*3", beginning(), &ParseOptions::default()).unwrap();
+        let (_context, right) = parse_expression("9 + 8*7", beginning(), &ParseOptions::default()).unwrap();
+
+        assert!(left.same_shape(&right));
+    }
+
+    #[test]
+    fn test_same_shape_rejects_different_structure() {
+        let (_context, left) = parse_expression("1 + 2*3", beginning(), &ParseOptions::default()).unwrap();
+        let (_context, right) = parse_expression("1 + 2", beginning(), &ParseOptions::default()).unwrap();
+
+        assert!(!left.same_shape(&right));
+    }
+
+    #[test]
+    fn test_equivalent_ignores_commutative_operand_order() {
+        let (_context, left) = parse_expression("2 + 3 * 4", beginning(), &ParseOptions::default()).unwrap();
+        let (_context, right) = parse_expression("4 * 3 + 2", beginning(), &ParseOptions::default()).unwrap();
+
+        assert!(left.equivalent(&right));
+    }
+
+    #[test]
+    fn test_equivalent_respects_non_commutative_operand_order() {
+        let (_context, left) = parse_expression("5 - 1", beginning(), &ParseOptions::default()).unwrap();
+        let (_context, right) = parse_expression("1 - 5", beginning(), &ParseOptions::default()).unwrap();
+
+        assert!(!left.equivalent(&right));
+    }
+
+    #[test]
+    fn test_operator_built_sum_is_structurally_eq_to_parsed_sum() {
+        let built = ExpressionNode::from(1) + ExpressionNode::from(2);
+        let (_context, parsed) = parse_expression("1 + 2", beginning(), &ParseOptions::default()).unwrap();
+
+        assert!(built.structurally_eq(&parsed));
+    }
+
+    #[test]
+    fn test_operator_built_difference_product_quotient_are_structurally_eq_to_parsed() {
+        let (_context, parsed_difference) = parse_expression("1 - 2", beginning(), &ParseOptions::default()).unwrap();
+        assert!((ExpressionNode::from(1) - ExpressionNode::from(2)).structurally_eq(&parsed_difference));
+
+        let (_context, parsed_product) = parse_expression("1 * 2", beginning(), &ParseOptions::default()).unwrap();
+        assert!((ExpressionNode::from(1) * ExpressionNode::from(2)).structurally_eq(&parsed_product));
+
+        let (_context, parsed_quotient) = parse_expression("1 / 2", beginning(), &ParseOptions::default()).unwrap();
+        assert!((ExpressionNode::from(1) / ExpressionNode::from(2)).structurally_eq(&parsed_quotient));
+    }
+
+    #[test]
+    fn test_operator_built_expression_evaluates_like_a_parsed_equivalent() {
+        let built = ExpressionNode::from(1) + ExpressionNode::from(2.5);
+        let (_context, parsed) = parse_expression("1 + 2.5", beginning(), &ParseOptions::default()).unwrap();
+
+        assert_eq!(parsed.evaluate(), built.evaluate());
+    }
+
+    #[test]
+    fn test_display_sum_needs_no_parens_around_nested_product() {
+        let (_context, node) = parse_expression("2 + 3 * 4", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!("2 + 3 * 4", node.to_string());
+    }
+
+    #[test]
+    fn test_display_product_wraps_nested_sum_in_parens() {
+        let (_context, node) = parse_expression("(2 + 3) * 4", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!("(2 + 3) * 4", node.to_string());
+    }
+
+    #[test]
+    fn test_display_sum_of_two_operands_does_not_duplicate_the_first() {
+        let (_context, node) = parse_expression("2 + 3", beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!("2 + 3", node.to_string());
+    }
+
+    #[test]
+    fn test_display_difference_wraps_trailing_sum_but_not_leading() {
+        // `(a + b) - c` needs no parens: a leading `Sum` operand of a `Difference`
+        // doesn't change meaning, since it's still evaluated first either way.
+        let left = (ExpressionNode::from(1) + ExpressionNode::from(2)) - ExpressionNode::from(3);
+        assert_eq!("1 + 2 - 3", left.to_string());
+
+        // `a - (b + c)` does need parens: without them, `a - b + c` would mean
+        // `(a - b) + c`, not `a - (b + c)`.
+        let right = ExpressionNode::from(1) - (ExpressionNode::from(2) + ExpressionNode::from(3));
+        assert_eq!("1 - (2 + 3)", right.to_string());
+    }
+
+    #[test]
+    fn test_display_power_wraps_lower_precedence_base_and_exponent() {
+        let built = ExpressionNode::Power {
+            position: ParsePosition::default(),
+            base: Box::new(ExpressionNode::from(1) + ExpressionNode::from(2)),
+            exponent: Box::new(ExpressionNode::from(3) * ExpressionNode::from(4)),
+        };
+        assert_eq!("(1 + 2)^(3 * 4)", built.to_string());
+    }
+
+    #[test]
+    fn test_display_round_trips_through_reparse() {
+        for s in ["2 + 3 * 4", "(2 + 3) * 4", "2 * 3 + 4", "10 - 2 - 3", "10 / 2 / 5", "2^3"] {
+            let (_context, node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+            let formatted = node.to_string();
+            let (_context, reparsed) = parse_expression(&formatted, beginning(), &ParseOptions::default()).unwrap();
+            assert!(node.structurally_eq(&reparsed), "{} formatted as {} did not round-trip", s, formatted);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_all_evaluates_each_node_in_order() {
+        let nodes: Vec<ExpressionNode> = vec!["1 + 1", "2 * 3", "10 / 2"]
+            .into_iter()
+            .map(|s| parse_expression(s, beginning(), &ParseOptions::default()).unwrap().1)
+            .collect();
+
+        assert_eq!(
+            vec![
+                ExpressionValue::Integer { value: 2 },
+                ExpressionValue::Integer { value: 6 },
+                ExpressionValue::Integer { value: 5 },
+            ],
+            evaluate_all(&nodes)
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_evaluate_batch_parallel_matches_sequential_results() {
+        use super::evaluate_batch_parallel;
+
+        let nodes: Vec<ExpressionNode> = (0..1000)
+            .map(|i| parse_expression(&format!("{} + 1", i), beginning(), &ParseOptions::default()).unwrap().1)
+            .collect();
+
+        assert_eq!(evaluate_all(&nodes), evaluate_batch_parallel(&nodes));
+    }
+
+    #[test]
+    fn test_evaluate_table_power_of_variable() {
+        // x^2, built by hand rather than parsed, to keep this test focused on evaluate_table.
+        let node = ExpressionNode::Power {
+            position: ParsePosition::default(),
+            base: Box::new(ExpressionNode::Variable { position: ParsePosition::default(), name: "x".to_string() }),
+            exponent: Box::new(ExpressionNode::Integer { position: ParsePosition::default(), value: 2 }),
+        };
+        let values = node.evaluate_table("x", &[0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(
+            vec![
+                ExpressionValue::Decimal { value: 0.0 },
+                ExpressionValue::Decimal { value: 1.0 },
+                ExpressionValue::Decimal { value: 4.0 },
+                ExpressionValue::Decimal { value: 9.0 },
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn test_evaluate_with_resolves_bound_variables() {
+        // x*2 + y, built by hand rather than parsed, to keep this test focused on evaluate_with.
+        let node = ExpressionNode::Sum {
+            position: ParsePosition::default(),
+            operands: vec!(
+                ExpressionNode::Product {
+                    position: ParsePosition::default(),
+                    operands: vec!(
+                        ExpressionNode::Variable { position: ParsePosition::default(), name: "x".to_string() },
+                        ExpressionNode::Integer { position: ParsePosition::default(), value: 2 },
+                    ),
+                },
+                ExpressionNode::Variable { position: ParsePosition::default(), name: "y".to_string() },
+            ),
+        };
+
+        let mut bindings = HashMap::new();
+        bindings.insert("x".to_string(), ExpressionValue::Integer { value: 3 });
+        bindings.insert("y".to_string(), ExpressionValue::Integer { value: 4 });
+
+        assert_eq!(ExpressionValue::Integer { value: 10 }, node.evaluate_with(&bindings));
+    }
+
+    #[test]
+    fn test_evaluate_with_unbound_variable_is_nan() {
+        let node = ExpressionNode::Variable { position: ParsePosition::default(), name: "x".to_string() };
+
+        assert_eq!(ExpressionValue::NaN { reason: None }, node.evaluate_with(&HashMap::new()));
+        assert_eq!(ExpressionValue::NaN { reason: None }, node.evaluate());
     }
 }
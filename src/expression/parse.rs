@@ -11,7 +11,7 @@
 //!
 //! Parses the following PEG grammar:
 //!
-//! ```
+//! ```text
 //! digit ::= [0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9]
 //! sign ::= '-'
 //! integer ::= {sign} [digit]*
@@ -25,7 +25,8 @@
 //! product ::= quotient {['×' | '*']  quotient}*
 //! difference ::= product  {'-' product}*
 //! sum ::= difference {'+' difference}*
-//! expression ::= sum
+//! comparison ::= sum {['<' | '<=' | '>' | '>=' | '==' | '!='] sum}*
+//! expression ::= comparison
 //!
 //! Key to PEG notation:
 //! {} = optional, choose zero or one
@@ -35,7 +36,7 @@
 //!
 //! Usage:
 //!   let s = " (((10 + 5) * -6) - -20.0 / -2 * 3 + -((5*2)^2) - (-5 * -2 * 5)) ";
-//!   let (_result_context, result_node) = parse(s, beginning()).unwrap();
+//!   let (_result_context, result_node) = parse(s, beginning(), &ParseOptions::default()).unwrap();
 //!   assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: -270 as DecimalType});
 //! ```
 //!
@@ -45,19 +46,156 @@ use crate::scan::context::{
     ScanContext,
     scan_one_or_more_chars,
     scan_literal,
-    scan_zero_or_more_chars
+    scan_signed_int,
+    scan_zero_or_more_chars,
+    scan_identifier,
+    scanned_str,
+    peek_str,
+    beginning,
 };
 
 use crate::expression::position::ParsePosition;
-use crate::expression::error::ParsingError;
+use crate::expression::error::{ParsingError, NumberError, NumberParseError};
+
+use super::node::{ExpressionNode, constant_value, is_known_function_name};
+use super::value::{SignType, ExpressionValue, DecimalType, IntegerType, ComparisonOp};
 
-use super::node::ExpressionNode;
-use super::value::SignType;
 
 
+///
+/// Options that customize how parsing is performed.
+///
+/// `extra_whitespace` lists additional characters that should be skipped
+/// wherever whitespace is skipped, alongside the standard ASCII whitespace
+/// characters. This is useful for inputs with separators that aren't
+/// operators, e.g. treating `,` as whitespace so `1 ,+ 2` parses the same as
+/// `1 + 2` (once commas are configured as whitespace, they're simply
+/// invisible to the grammar; the operators still have to appear explicitly,
+/// so `1 , 2 , 3` is not a sum).
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseOptions {
+    pub extra_whitespace: Vec<char>,
+    // when true, a `Parenthesis` missing its closing `)` at end-of-input is
+    // treated as implicitly closed there, instead of erroring. Default false.
+    pub auto_close_parens: bool,
+    // when set, a number literal whose magnitude exceeds this bound (or that
+    // parses to infinite/NaN, e.g. `1e400`) is rejected with
+    // `ParsingError::Number(_, NumberError::OutOfRange(None))` instead of being
+    // accepted. Default None, which accepts any literal the underlying
+    // number type can represent.
+    pub max_abs_value: Option<DecimalType>,
+    // when set, an n-ary node (e.g. a `Sum`) that collects more than this
+    // many operands is rejected with `ParsingError::TooManyOperands(_)`
+    // instead of being accepted, to defend against adversarial input like a
+    // sum of a million terms exhausting memory. Default None, which accepts
+    // any number of operands.
+    pub max_operands: Option<usize>,
+    // when true, whitespace is skipped using `char::is_whitespace()`, which
+    // also recognizes Unicode whitespace like the non-breaking space U+00A0
+    // (common in copy-pasted input). Default false, which only skips ASCII
+    // whitespace via `char::is_ascii_whitespace()`, the cheaper check.
+    pub unicode_whitespace: bool,
+}
+
+///
+/// Check a parsed number's magnitude against `options.max_abs_value`.
+/// No-op (always `Ok`) when `max_abs_value` is `None`.
+///
+fn check_magnitude(value: DecimalType, position: &ParsePosition, options: &ParseOptions) -> Result<(), ParsingError> {
+    if let Some(max_abs_value) = options.max_abs_value {
+        if !value.is_finite() || value.abs() > max_abs_value {
+            return Err(ParsingError::Number(position.clone(), NumberError::OutOfRange(None)));
+        }
+    }
+    Ok(())
+}
 
-fn scan_whitespace(s: &str, context: ScanContext) -> ScanContext {
-    scan_zero_or_more_chars(s, context, |ch| ch.is_ascii_whitespace())
+///
+/// Check an in-progress operand count against `options.max_operands`.
+/// No-op (always `Ok`) when `max_operands` is `None`.
+///
+fn check_operand_count(operand_count: usize, position: &ParsePosition, options: &ParseOptions) -> Result<(), ParsingError> {
+    if let Some(max_operands) = options.max_operands {
+        if operand_count > max_operands {
+            return Err(ParsingError::TooManyOperands(position.clone()));
+        }
+    }
+    Ok(())
+}
+
+///
+/// Scan for `primary`, falling back to `alternate` if `primary` isn't found
+/// (e.g. the ASCII `*` and its Unicode alternative `×`), the same
+/// try-then-fall-back shape `scan_number_span` uses for the `e`/`E` exponent
+/// marker.
+///
+fn scan_operator(s: &str, context: ScanContext, primary: &'static str, alternate: &'static str) -> ScanContext {
+    let result = scan_literal(s, context, primary);
+    if result.0 {
+        result
+    } else {
+        scan_literal(s, context, alternate)
+    }
+}
+
+///
+/// Scan for one of the six comparison operators. Each two-character operator
+/// is tried before the one-character operator it's a prefix of (`<=` before
+/// `<`, `>=` before `>`) so a trailing `=` is never left stranded as the start
+/// of the next operand.
+///
+fn scan_comparison_operator(s: &str, context: ScanContext) -> (ScanContext, Option<ComparisonOp>) {
+    const OPERATORS: [(&str, ComparisonOp); 6] = [
+        ("<=", ComparisonOp::LessOrEqual),
+        (">=", ComparisonOp::GreaterOrEqual),
+        ("==", ComparisonOp::Equal),
+        ("!=", ComparisonOp::NotEqual),
+        ("<", ComparisonOp::LessThan),
+        (">", ComparisonOp::GreaterThan),
+    ];
+    for (symbol, op) in OPERATORS {
+        let result = scan_literal(s, context, symbol);
+        if result.0 {
+            return (result, Some(op));
+        }
+    }
+    ((false, context.1), None)
+}
+
+fn scan_whitespace(s: &str, context: ScanContext, options: &ParseOptions) -> ScanContext {
+    // `scan_zero_or_more_chars` only accepts a non-capturing `fn(char) -> bool`,
+    // so when there's no extra whitespace configured, use it directly...
+    if options.extra_whitespace.is_empty() {
+        return if options.unicode_whitespace {
+            scan_zero_or_more_chars(s, context, |ch| ch.is_whitespace())
+        } else {
+            scan_zero_or_more_chars(s, context, |ch| ch.is_ascii_whitespace())
+        };
+    }
+
+    // ...otherwise fall back to scanning inline, since the extra-whitespace
+    // test needs to capture `options`.
+    let (matched, mut position) = context;
+    if (!matched) || position.byte_index > s.len() {
+        return (false, position);
+    }
+
+    for ch in s[position.byte_index..].chars() {
+        let is_whitespace = if options.unicode_whitespace { ch.is_whitespace() } else { ch.is_ascii_whitespace() };
+        if !(is_whitespace || options.extra_whitespace.contains(&ch)) {
+            return (true, position);
+        }
+        if ch == '\n' {
+            position.line_index += 1;
+            position.line_byte_index = position.byte_index + '\n'.len_utf8();
+            position.line_char_index = position.char_index + 1;
+        }
+        position.byte_index += ch.len_utf8();
+        position.char_index += 1;
+    }
+
+    (true, position)
 }
 fn scan_digits(s: &str, context: ScanContext) -> ScanContext {
     scan_one_or_more_chars(s, context, |ch| ch.is_ascii_digit())
@@ -77,7 +215,7 @@ fn expect_match(s: &str, start_position: ScanPosition, context: ScanContext) ->
         if position.byte_index >= s.len() {
             Err(ParsingError::EndOfInput(ParsePosition::new(&start_position, &position)))
         } else {
-            Err(ParsingError::Number(ParsePosition::new(&start_position, &position)))
+            Err(ParsingError::Number(ParsePosition::new(&start_position, &position), NumberError::NoDigits))
         }
     } else {
         Ok(context)
@@ -86,23 +224,30 @@ fn expect_match(s: &str, start_position: ScanPosition, context: ScanContext) ->
 
 
 
-fn parse_whitespace(s: &str, context: ScanContext) -> Result<ScanContext, ParsingError> {
-    expect_match(s, context.1, scan_whitespace(s, context))
+fn parse_whitespace(s: &str, context: ScanContext, options: &ParseOptions) -> Result<ScanContext, ParsingError> {
+    expect_match(s, context.1, scan_whitespace(s, context, options))
 }
 
 ///
 /// Exhaustively parse the string.
 /// This will error is there are extra non-whitespace characters after the expression.
 ///
-pub fn parse(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
-    match parse_expression(s, context) {
+pub fn parse(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+    match parse_expression(s, context, options) {
         Ok((expression_context, expression_node)) => {
-            let (matched, position) = scan_whitespace(s, expression_context);
+            let (matched, position) = scan_whitespace(s, expression_context, options);
             if !matched || position.byte_index < s.len() {
-                Err(ParsingError::ExtraInput(ParsePosition {
-                    start: position,
-                    end: scan_to_end(s, (matched, position)).1  // scan to end of input
-                }))
+                if s[position.byte_index..].starts_with(')') {
+                    let mut end = position;
+                    end.byte_index += 1;
+                    end.char_index += 1;
+                    Err(ParsingError::UnbalancedParenthesis(ParsePosition { start: position, end }))
+                } else {
+                    Err(ParsingError::ExtraInput(ParsePosition {
+                        start: position,
+                        end: scan_to_end(s, (matched, position)).1  // scan to end of input
+                    }))
+                }
             } else {
                 Ok((expression_context, expression_node))
             }
@@ -111,18 +256,90 @@ pub fn parse(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNo
     }
 }
 
+///
+/// Parse an expression from any [std::io::Read] source (a file, a socket, etc).
+/// This buffers the entire input into a `String` before parsing, so it is not
+/// suitable for unbounded streams.
+///
+pub fn parse_from_reader<R: std::io::Read>(mut reader: R) -> Result<ExpressionNode, ParsingError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).map_err(|err| ParsingError::Io(err.to_string()))?;
+    let s = String::from_utf8(bytes).map_err(|err| ParsingError::Io(err.to_string()))?;
+    let (_context, node) = parse(&s, beginning(), &ParseOptions::default())?;
+    Ok(node)
+}
+
+///
+/// Parse and evaluate `s` in one call, with default [ParseOptions], for embedders that
+/// just want a result and don't want to touch [ScanContext]/[beginning] themselves. This
+/// is what `main.rs` does inline, pulled out so library callers don't have to repeat it.
+///
+pub fn evaluate_str(s: &str) -> Result<ExpressionValue, ParsingError> {
+    let (_context, node) = parse(s, beginning(), &ParseOptions::default())?;
+    Ok(node.evaluate())
+}
+
+///
+/// Build a tree from a Reverse Polish Notation token stream (e.g.
+/// `["1", "2", "+"]` for `1 + 2`), using a stack: a number token is pushed,
+/// and an operator token (`+`, `-`, `*`, `/`, `^`) pops its two operands
+/// (right operand first, since it's nearer the top of the stack) and
+/// pushes the resulting binary node. Positions in the built tree aren't
+/// meaningful, since there's no source span to point at, so they're all
+/// [ParsePosition::default].
+///
+/// Errors with [ParsingError::MissingOperand] if an operator is reached
+/// with fewer than two operands on the stack, [ParsingError::Number] if a
+/// token is neither a known operator nor a valid number, and
+/// [ParsingError::ExtraInput] if more than one value remains on the stack
+/// once every token is consumed (or [ParsingError::EndOfInput] if none do).
+///
+pub fn from_rpn(tokens: &[&str]) -> Result<ExpressionNode, ParsingError> {
+    let mut stack: Vec<ExpressionNode> = Vec::new();
+    for token in tokens {
+        match *token {
+            "+" | "-" | "*" | "/" | "^" => {
+                let operator = token.chars().next().unwrap();
+                let right = stack.pop().ok_or(ParsingError::MissingOperand(ParsePosition::default(), operator))?;
+                let left = stack.pop().ok_or(ParsingError::MissingOperand(ParsePosition::default(), operator))?;
+                let position = ParsePosition::default();
+                stack.push(match *token {
+                    "+" => ExpressionNode::Sum { position, operands: vec!(left, right) },
+                    "-" => ExpressionNode::Difference { position, operands: vec!(left, right) },
+                    "*" => ExpressionNode::Product { position, operands: vec!(left, right) },
+                    "/" => ExpressionNode::Quotient { position, operands: vec!(left, right) },
+                    _ => ExpressionNode::Power { position, base: Box::new(left), exponent: Box::new(right) },
+                });
+            },
+            other => {
+                let position = ParsePosition::default();
+                let node = other.parse::<IntegerType>().map(|value| ExpressionNode::Integer { position: position.clone(), value })
+                    .or_else(|_| other.parse::<DecimalType>().map(|value| ExpressionNode::Decimal { position: position.clone(), value }))
+                    .map_err(|_| ParsingError::Number(position, NumberError::NoDigits))?;
+                stack.push(node);
+            },
+        }
+    }
+
+    match stack.len() {
+        0 => Err(ParsingError::EndOfInput(ParsePosition::default())),
+        1 => Ok(stack.pop().unwrap()),
+        _ => Err(ParsingError::ExtraInput(ParsePosition::default())),
+    }
+}
+
 ///
 /// Parse the expression and return where it ends.
-/// ```
-/// expression ::= sum
+/// ```text
+/// expression ::= comparison
 /// ```
 ///
-pub fn parse_expression(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
-    parse_sum(s, context)
+pub fn parse_expression(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+    parse_comparison(s, context, options)
 }
 
-pub fn print_expression_result(s: &str, context:ScanContext) {
-    match parse_expression(s, context) {
+pub fn print_expression_result(s: &str, context:ScanContext, options: &ParseOptions) {
+    match parse_expression(s, context, options) {
         Ok((_context, expression_node)) => {
             println!("{} = {}", &s[expression_node.position().start.byte_index..expression_node.position().end.byte_index], expression_node.evaluate());
         },
@@ -138,8 +355,8 @@ pub fn print_expression_result(s: &str, context:ScanContext) {
     }
 }
 
-pub fn print_result(s: &str, context:ScanContext) {
-    match parse_expression(s, context) {
+pub fn print_result(s: &str, context:ScanContext, options: &ParseOptions) {
+    match parse_expression(s, context, options) {
         Ok((_context, expression_node)) => {
             println!("{}", expression_node.evaluate());
         },
@@ -158,20 +375,34 @@ pub fn print_result(s: &str, context:ScanContext) {
 ///
 /// Parse a number.
 ///
-/// ```
+/// ```text
 ///  digit ::= [0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9]
 ///  sign ::= '-'
 ///  integer ::= {sign} [digit]*
-///  decimal ::= {sign} [digit]* '.' [digit]*
+///  decimal ::= {sign} ([digit]* '.' [digit]+ | [digit]+ '.' [digit]*)
 ///  scientific ::= {sign} [digit]* {'.' [digit]*} ['e' | 'E'] {sign} [digit]*
 ///  number ::= [integer | decimal | scientific]
 /// ```
 ///
-fn parse_number(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+/// NOTE: a leading decimal point is allowed when followed by digits (`.5` == `0.5`)
+///       and a trailing decimal point is allowed when preceded by digits (`5.` == `5.0`),
+///       but a bare `.` (no digits on either side) is not a valid number.
+///
+///
+/// Scan the span of a number (integer, decimal or scientific notation)
+/// without converting it to a value, so callers can either build an
+/// [ExpressionNode] (see [parse_number]) or an [ExpressionValue]
+/// (see [scan_number_value]) from the same scan.
+///
+/// Returns the scanned span's `ScanContext`, the position where the span
+/// starts and whether the span is a decimal (has a decimal point and/or
+/// an exponent) rather than a plain integer.
+///
+fn scan_number_span(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ScanPosition, bool), ParsingError> {
     //
     // skip any leading whitespace
     //
-    let (mut _matched, start_position) = parse_whitespace(s, context)?;
+    let (mut _matched, start_position) = parse_whitespace(s, context, options)?;
 
     //
     // parse the optional negation
@@ -179,131 +410,318 @@ fn parse_number(s: &str, context: ScanContext) -> Result<(ScanContext, Expressio
     let (_is_negative, mut position) = scan_literal(s, (true, start_position), "-");
 
     //
-    // scan the required integer part
+    // scan the optional integer part; digits here are not required
+    // since a leading decimal point (`.5`) is allowed.
     //
-    (_matched, position) = expect_match(s, start_position, scan_digits(s, (true, position)))?;
+    let integer_start = position;
+    (_matched, position) = scan_digits(s, (true, position));
+    let has_integer_digits = position.byte_index > integer_start.byte_index;
 
     //
-    // scan the optional decimal part
+    // scan the optional decimal part; digits after the point are not required
+    // since a trailing decimal point (`5.`) is allowed.
     //
     let is_decimal;
     (is_decimal, position) = scan_literal(s, (true, position), ".");
+    let mut has_fraction_digits = false;
     if is_decimal {
-        (_matched, position) = expect_match(s, start_position, scan_digits(s, (true, position)))?;
+        let fraction_start = position;
+        (_matched, position) = scan_digits(s, (true, position));
+        has_fraction_digits = position.byte_index > fraction_start.byte_index;
+
+        //
+        // a second decimal point immediately following is invalid (e.g. "1.2.3")
+        //
+        let (has_second_point, second_point_position) = scan_literal(s, (true, position), ".");
+        if has_second_point {
+            return Err(ParsingError::Number(ParsePosition::new(&start_position, &second_point_position), NumberError::MultipleDecimalPoints));
+        }
     }
 
     //
-    // scan the optional exponent
+    // a number must have at least one digit somewhere,
+    // so reject a bare "." (or a bare "-") with no digits on either side.
+    //
+    if !has_integer_digits && !has_fraction_digits {
+        if position.byte_index >= s.len() {
+            return Err(ParsingError::EndOfInput(ParsePosition::new(&start_position, &position)));
+        } else {
+            return Err(ParsingError::Number(ParsePosition::new(&start_position, &position), NumberError::NoDigits));
+        }
+    }
+
+    //
+    // scan the optional exponent; its digits may carry a sign ('1e-5', '1e+5')
     //
     let (mut has_exponent, mut exponent_position) = scan_literal(s, (true, position), "e");
     if !has_exponent {
         (has_exponent, exponent_position) = scan_literal(s, (true, position), "E");
     }
     if has_exponent {
-        (_matched, position) = expect_match(s, start_position, scan_digits(s, (true, exponent_position)))?;
+        let exponent_context = scan_signed_int(s, (true, exponent_position));
+        if !exponent_context.0 {
+            return Err(ParsingError::Number(ParsePosition::new(&start_position, &exponent_context.1), NumberError::ExponentWithoutDigits));
+        }
+        position = exponent_context.1;
     }
 
+    Ok(((true, position), start_position, is_decimal || has_exponent))
+}
+
+///
+/// Parse a number.
+///
+/// ```text
+///  digit ::= [0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9]
+///  sign ::= '-'
+///  integer ::= {sign} [digit]*
+///  decimal ::= {sign} ([digit]* '.' [digit]+ | [digit]+ '.' [digit]*)
+///  scientific ::= {sign} [digit]* {'.' [digit]*} ['e' | 'E'] {sign} [digit]*
+///  number ::= [integer | decimal | scientific]
+/// ```
+///
+/// NOTE: a leading decimal point is allowed when followed by digits (`.5` == `0.5`)
+///       and a trailing decimal point is allowed when preceded by digits (`5.` == `5.0`),
+///       but a bare `.` (no digits on either side) is not a valid number.
+///
+fn parse_number(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+    let ((matched, position), start_position, is_decimal_value) = scan_number_span(s, context, options)?;
+
     //
     // return the scanned value
     //
-    Ok(((true, position), if is_decimal || has_exponent {
+    let number_position = ParsePosition::new(&start_position, &position);
+    let number_str = scanned_str(s, start_position, (matched, position)).ok_or_else(|| ParsingError::Number(number_position.clone(), NumberError::NoDigits))?;
+    Ok(((matched, position), if is_decimal_value {
+            let value = number_str.parse::<f64>()
+                .map_err(|err| ParsingError::Number(number_position.clone(), NumberError::OutOfRange(Some(NumberParseError::new(err)))))?;
+            check_magnitude(value, &number_position, options)?;
             ExpressionNode::Decimal{
-                position: ParsePosition::new(&start_position, &position),
-                value: s[start_position.byte_index..position.byte_index].parse::<f64>().map_err(|err| {
-                    println!("Error converting decimal number at {:?}: {}", ParsePosition::new(&start_position, &position), &err);
-                    ParsingError::Number(ParsePosition::new(&start_position, &position))
-                })?
+                position: number_position,
+                value
             }
         } else {
             // integer
+            let value = number_str.parse::<i32>()
+                .map_err(|err| ParsingError::Number(number_position.clone(), NumberError::OutOfRange(Some(NumberParseError::new(err)))))?;
+            check_magnitude(value as DecimalType, &number_position, options)?;
             ExpressionNode::Integer{
-                position: ParsePosition::new(&start_position, &position),
-                value: s[start_position.byte_index..position.byte_index].parse::<i32>().map_err(|err| {
-                    println!("Error converting integer at {:?}: {}", ParsePosition::new(&start_position, &position), &err);
-                    ParsingError::Number(ParsePosition::new(&start_position, &position))
-                })?
+                position: number_position,
+                value
             }
         }
     ))
 }
 
+///
+/// Scan a number and directly return its value as an [ExpressionValue]
+/// (`Integer` or `Decimal`), without going through an [ExpressionNode].
+/// This lets callers that only need the value, not a positioned AST node,
+/// avoid re-slicing and re-parsing the matched text themselves.
+///
+/// Returns `None`, with the `ScanContext` unchanged, when no number matches.
+///
+pub fn scan_number_value(s: &str, context: ScanContext, options: &ParseOptions) -> (ScanContext, Option<ExpressionValue>) {
+    match scan_number_span(s, context, options) {
+        Ok((new_context, start_position, is_decimal_value)) => {
+            let (_matched, position) = new_context;
+            let text = &s[start_position.byte_index..position.byte_index];
+            let value = if is_decimal_value {
+                text.parse::<f64>().ok().map(|value| ExpressionValue::Decimal { value })
+            } else {
+                text.parse::<i32>().ok().map(|value| ExpressionValue::Integer { value })
+            };
+            (new_context, value)
+        },
+        Err(_) => (context, None),
+    }
+}
+
 ///
 /// Parse a parenthesized expression.
 ///
-/// ```
-/// value ::= [parenthesis | number]
+/// ```text
+/// value ::= [radical | identifier | parenthesis | number] {'°'}
+/// radical ::= '√' value
+/// identifier ::= [a-zA-Z_][a-zA-Z0-9_]* {'(' expression ')'}
 /// parenthesis ::= {sign} '(' expression ')'
 /// ```
 ///
-fn parse_value(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+/// NOTE: `√` is parsed as a unary `sqrt` [ExpressionNode::Function] applied
+///       to the value that follows it, so both `√4` and `√(9)` are supported.
+///
+/// NOTE: an identifier directly followed by `(` is a named [ExpressionNode::Function]
+///       call taking a single argument (e.g. `sqrt(4)`); an identifier with no
+///       following `(` is a bare [ExpressionNode::Variable] (e.g. `x`), unless
+///       it names a recognized constant (`pi`, `π`, or `e`), in which case it's
+///       an [ExpressionNode::Constant] instead.
+///
+/// NOTE: a trailing `°` wraps whatever value precedes it (a number or a
+///       parenthesis) in an [ExpressionNode::Degrees], converting it from
+///       degrees to radians when evaluated, so `90°` and `(90)°` are both
+///       supported.
+///
+fn parse_value(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
     //
     // skip any leading whitespace
     //
-    let (mut matched, start_position) = parse_whitespace(s, context)?;
+    let (mut matched, start_position) = parse_whitespace(s, context, options)?;
 
     //
-    // parse the optional negation
+    // parse the optional radical ('√'), which applies sqrt to the following value
     //
-    let (is_negative, mut position) = scan_literal(s, (matched, start_position), "-");
+    let (is_radical, radical_position) = scan_literal(s, (matched, start_position), "√");
+    if is_radical {
+        let (new_context, argument) = parse_value(s, (true, radical_position), options)?;
+        let (matched, position) = new_context;
+        return Ok(((matched, position), ExpressionNode::Function {
+                position: ParsePosition::new(&start_position, &position),
+                name: "sqrt".to_string(),
+                argument: Box::new(argument),
+            }
+        ));
+    }
 
     //
-    // scan opening brace
+    // parse an identifier, which is either a named function call (followed
+    // directly by '(') or a bare variable reference
     //
-    (matched, position) = scan_literal(s, (matched, position), "(");
-    if matched {
+    let (is_identifier, identifier_position) = scan_identifier(s, (matched, start_position));
+    let (new_context, value_node) = if is_identifier {
+        let name = s[start_position.byte_index..identifier_position.byte_index].to_string();
+
+        let (has_paren, paren_position) = scan_literal(s, (true, identifier_position), "(");
+        if has_paren {
+            let argument: ExpressionNode;
+            let mut position;
+
+            ((matched, position), argument) = parse_expression(s, (true, paren_position), options)?;
+
+            let whitespace_context = parse_whitespace(s, (matched, position), options)?;
+            let close_context = scan_literal(s, whitespace_context, ")");
+            (matched, position) = if !close_context.0 && options.auto_close_parens && whitespace_context.1.byte_index >= s.len() {
+                (true, whitespace_context.1)
+            } else {
+                expect_match(s, start_position, close_context)?
+            };
+
+            if !is_known_function_name(&name) {
+                return Err(ParsingError::UnknownFunction(ParsePosition::new(&start_position, &position), name));
+            }
+
+            ((matched, position), ExpressionNode::Function {
+                    position: ParsePosition::new(&start_position, &position),
+                    name,
+                    argument: Box::new(argument),
+                }
+            )
+        } else if constant_value(&name).is_some() {
+            ((true, identifier_position), ExpressionNode::Constant {
+                    position: ParsePosition::new(&start_position, &identifier_position),
+                    name,
+                }
+            )
+        } else {
+            ((true, identifier_position), ExpressionNode::Variable {
+                    position: ParsePosition::new(&start_position, &identifier_position),
+                    name,
+                }
+            )
+        }
+    } else {
         //
-        // parse the expression inside the parenthesis
+        // parse the optional negation
         //
-        let inner_node: ExpressionNode;
-
-        ((matched, position), inner_node) = parse_expression(s, (matched, position))?;
+        let (is_negative, mut position) = scan_literal(s, (matched, start_position), "-");
 
         //
-        // scan the required closing parenthesis
+        // scan opening brace
         //
-        (matched, position) = expect_match(s, start_position, scan_literal(s, parse_whitespace(s, (matched, position))?, ")"))?;
+        (matched, position) = scan_literal(s, (matched, position), "(");
+        if matched {
+            //
+            // parse the expression inside the parenthesis
+            //
+            let inner_node: ExpressionNode;
+
+            ((matched, position), inner_node) = parse_expression(s, (matched, position), options)?;
+
+            //
+            // scan the closing parenthesis; when `auto_close_parens` is enabled and
+            // input ends before a `)` is found, treat it as present at end-of-input
+            // rather than erroring, so lenient/interactive callers can parse partial input.
+            //
+            let whitespace_context = parse_whitespace(s, (matched, position), options)?;
+            let close_context = scan_literal(s, whitespace_context, ")");
+            (matched, position) = if !close_context.0 && options.auto_close_parens && whitespace_context.1.byte_index >= s.len() {
+                (true, whitespace_context.1)
+            } else {
+                expect_match(s, start_position, close_context)?
+            };
 
-        Ok(((matched, position), ExpressionNode::Parenthesis {
-                position: ParsePosition::new(&start_position, &position),
-                sign: SignType::from(!is_negative),
-                inner: Box::new(inner_node),
+            ((matched, position), ExpressionNode::Parenthesis {
+                    position: ParsePosition::new(&start_position, &position),
+                    sign: SignType::from(!is_negative),
+                    inner: Box::new(inner_node),
+                }
+            )
+
+        } else {
+            //
+            // if it's not a parenthesis, then it must be a number.
+            // start at the optional negation
+            //
+            parse_number(s, (true, start_position), options)?
+        }
+    };
+
+    //
+    // parse the optional postfix degree symbol ('°'), which converts
+    // the value that precedes it from degrees to radians
+    //
+    let (matched, position) = new_context;
+    let (is_degrees, degrees_position) = scan_literal(s, (matched, position), "°");
+    if is_degrees {
+        Ok(((true, degrees_position), ExpressionNode::Degrees {
+                position: ParsePosition::new(&start_position, &degrees_position),
+                inner: Box::new(value_node),
             }
         ))
-
     } else {
-        //
-        // if it's not a parenthesis, then it must be a number.
-        // start at the optional negation
-        //
-        parse_number(s, (true, start_position))
+        Ok(((matched, position), value_node))
     }
 }
 
 ///
 /// Parse an exponentiation expression.
 ///
-/// ```
+/// ```text
 /// power ::= value{'^'value}
 /// ```
 ///
-fn parse_power(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+fn parse_power(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
     const OPERATOR: &str = "^";
+    const ALTERNATE_OPERATOR: &str = "**";
 
     //
     // skip any leading whitespace
     //
-    let (matched, start_position) = parse_whitespace(s, context)?;
+    let (matched, start_position) = parse_whitespace(s, context, options)?;
 
 
-    let ((matched, left_position), left_node) = parse_value(s, (matched, start_position))?;
+    let ((matched, left_position), left_node) = parse_value(s, (matched, start_position), options)?;
 
     //
-    // scan operator
+    // scan operator; `**` is checked here (the tightest-binding level), rather
+    // than being left for `parse_product` to notice as two adjacent `*`s, so
+    // it's always consumed as a single power operator before a looser-binding
+    // level ever gets a chance to mis-scan its first `*` as multiplication
     //
-    let (matched, position) = scan_literal(s, (matched, left_position), OPERATOR);
+    let operator_position = parse_whitespace(s, (matched, left_position), options)?.1;
+    let (matched, position) = scan_operator(s, (matched, operator_position), OPERATOR, ALTERNATE_OPERATOR);
     if matched {
         // scan right side operand
-        let ((_matched, right_position), right_node) = parse_value(s, (matched, position))?;
+        let ((_matched, right_position), right_node) = parse_value(s, (matched, position), options)?;
 
         Ok(((true, right_position), ExpressionNode::Power {
                 position: ParsePosition::new(&start_position, &right_position),
@@ -321,28 +739,119 @@ fn parse_power(s: &str, context: ScanContext) -> Result<(ScanContext, Expression
 }
 
 ///
-/// Parse a series of addition operations.
+/// Parse a chain of comparisons.
 ///
+/// ```text
+/// comparison ::= sum {['<' | '<=' | '>' | '>=' | '==' | '!='] sum}*
 /// ```
+///
+/// Unlike the arithmetic levels below it, a run of comparisons chains with
+/// `&&` rather than nesting (e.g. `1 < 2 < 3` is `(1 < 2) && (2 < 3)`, not
+/// `(1 < 2) < 3`), so the operators seen between operands are kept alongside
+/// them in [ExpressionNode::ComparisonChain].
+///
+fn parse_comparison(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+    //
+    // skip any leading whitespace
+    //
+    let (matched, start_position) = parse_whitespace(s, context, options)?;
+
+    let ((matched, mut operand_position), left_node) = parse_sum(s, (matched, start_position), options)?;
+    let end_position = operand_position;
+
+    //
+    // scan operator
+    //
+    let mut operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+    let ((mut matched, mut position), mut next_op) = scan_comparison_operator(s, (true, operator_position));
+    if matched {
+        //
+        // collect up all the operands and the operators between them
+        //
+        let mut operands = Vec::with_capacity(2);
+        operands.push(left_node);
+        let mut ops = Vec::with_capacity(1);
+        while matched {
+            let op = next_op.expect("scan_comparison_operator reported a match without an operator");
+            let parse_node: ExpressionNode;
+
+            // scan next operand
+            match parse_sum(s, (matched, position), options) {
+                Ok(((new_matched, new_position), node)) => {
+                    matched = new_matched;
+                    operand_position = new_position;
+                    parse_node = node;
+                },
+                Err(ParsingError::EndOfInput(_)) => {
+                    return Err(ParsingError::MissingOperand(ParsePosition::new(&operator_position, &position), op.symbol().chars().next().unwrap()));
+                },
+                Err(other) => return Err(other),
+            }
+
+            // add it to the operands
+            ops.push(op);
+            operands.push(parse_node);
+            check_operand_count(operands.len(), &ParsePosition::new(&start_position, &operand_position), options)?;
+
+            // scan next operator
+            operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+            ((matched, position), next_op) = scan_comparison_operator(s, (true, operator_position));
+        }
+
+        Ok(((true, operand_position), ExpressionNode::ComparisonChain {
+                position: ParsePosition::new(&start_position, &operand_position),
+                operands,
+                ops,
+            }
+        ))
+
+    } else {
+        //
+        // no operator, so just return the left expression
+        //
+        Ok(((true, end_position), left_node))
+    }
+}
+
+///
+/// Cheaply estimate how many operands a chain of binary operations will have,
+/// so the operand `Vec` can be preallocated with `Vec::with_capacity` and
+/// avoid reallocating as operands are pushed.
+///
+/// This is just a lookahead count of the remaining occurrences of `operator`
+/// from `byte_index` to the end of the input; it doesn't need to be exact
+/// (an operator character appearing inside a later, unrelated, operand would
+/// only make the estimate too large, never too small against this grammar's
+/// left-to-right operand/operator structure), just cheap to compute.
+///
+fn estimate_operand_count(s: &str, byte_index: usize, operator: &str) -> usize {
+    1 + s[byte_index..].matches(operator).count()
+}
+
+///
+/// Parse a series of addition operations.
+///
+/// ```text
 /// sum ::= difference {'+' difference}*
 /// ```
 ///
-fn parse_sum(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+fn parse_sum(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
     const OPERATOR: &str = "+";
 
     //
     // skip any leading whitespace
     //
-    let (matched, start_position) = parse_whitespace(s, context)?;
+    let (matched, start_position) = parse_whitespace(s, context, options)?;
 
 
-    let ((matched, mut operand_position), left_node) = parse_difference(s, (matched, start_position))?;
+    let ((matched, mut operand_position), left_node) = parse_difference(s, (matched, start_position), options)?;
     let end_position = operand_position;
 
     //
     // scan operator
     //
-    let (mut matched, mut position) = scan_literal(s, parse_whitespace(s, (matched, operand_position))?, OPERATOR);
+    let mut operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+    let (mut matched, mut position) = scan_literal(s, (true, operator_position), OPERATOR);
     if matched {
         //
         // collect up all addends.
@@ -350,18 +859,31 @@ fn parse_sum(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNo
         // - put it into the vector
         // - put the vector into an sum expression node
         //
-        let mut addends = vec!(left_node);
+        let mut addends = Vec::with_capacity(estimate_operand_count(s, start_position.byte_index, OPERATOR));
+        addends.push(left_node);
         while matched {
             let parse_node: ExpressionNode;
 
             // scan next operand
-            ((matched, operand_position), parse_node) = parse_difference(s, (matched, position))?;
+            match parse_difference(s, (matched, position), options) {
+                Ok(((new_matched, new_position), node)) => {
+                    matched = new_matched;
+                    operand_position = new_position;
+                    parse_node = node;
+                },
+                Err(ParsingError::EndOfInput(_)) => {
+                    return Err(ParsingError::MissingOperand(ParsePosition::new(&operator_position, &position), '+'));
+                },
+                Err(other) => return Err(other),
+            }
 
             // add it to the operands
             addends.push(parse_node);
+            check_operand_count(addends.len(), &ParsePosition::new(&start_position, &operand_position), options)?;
 
             // scan next operator
-            (matched, position) = scan_literal(s, parse_whitespace(s, (matched, operand_position))?, OPERATOR);
+            operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+            (matched, position) = scan_literal(s, (true, operator_position), OPERATOR);
         }
 
         Ok(((true, operand_position), ExpressionNode::Sum {
@@ -383,26 +905,27 @@ fn parse_sum(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNo
 ///
 /// Parse a series of subtraction operations.
 ///
-/// ```
+/// ```text
 /// difference ::= product  {'-' product}*
 /// ```
 ///
-fn parse_difference(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+fn parse_difference(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
     const OPERATOR: &str = "-";
 
     //
     // skip any leading whitespace
     //
-    let (matched, start_position) = parse_whitespace(s, context)?;
+    let (matched, start_position) = parse_whitespace(s, context, options)?;
 
 
-    let ((matched, mut operand_position), left_node) = parse_product(s, (matched, start_position))?;
+    let ((matched, mut operand_position), left_node) = parse_product(s, (matched, start_position), options)?;
     let end_position = operand_position;
 
     //
     // scan operator
     //
-    let (mut matched, mut position) = scan_literal(s, parse_whitespace(s, (matched, operand_position))?, OPERATOR);
+    let mut operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+    let (mut matched, mut position) = scan_literal(s, (true, operator_position), OPERATOR);
     if matched {
         //
         // collect up all operands.
@@ -410,18 +933,31 @@ fn parse_difference(s: &str, context: ScanContext) -> Result<(ScanContext, Expre
         // - put it into the vector
         // - put the vector into an sum expression node
         //
-        let mut operands = vec!(left_node);
+        let mut operands = Vec::with_capacity(estimate_operand_count(s, start_position.byte_index, OPERATOR));
+        operands.push(left_node);
         while matched {
             let parse_node: ExpressionNode;
 
             // scan next operand
-            ((matched, operand_position), parse_node) = parse_product(s, (matched, position))?;
+            match parse_product(s, (matched, position), options) {
+                Ok(((new_matched, new_position), node)) => {
+                    matched = new_matched;
+                    operand_position = new_position;
+                    parse_node = node;
+                },
+                Err(ParsingError::EndOfInput(_)) => {
+                    return Err(ParsingError::MissingOperand(ParsePosition::new(&operator_position, &position), '-'));
+                },
+                Err(other) => return Err(other),
+            }
 
             // add it to the operands
             operands.push(parse_node);
+            check_operand_count(operands.len(), &ParsePosition::new(&start_position, &operand_position), options)?;
 
             // scan next operator
-            (matched, position) = scan_literal(s, parse_whitespace(s, (matched, operand_position))?, OPERATOR);
+            operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+            (matched, position) = scan_literal(s, (true, operator_position), OPERATOR);
         }
 
         Ok(((true, operand_position), ExpressionNode::Difference {
@@ -440,29 +976,47 @@ fn parse_difference(s: &str, context: ScanContext) -> Result<(ScanContext, Expre
 
 }
 
+///
+/// Scan for the `*`/`×` product operator at `context`, guarding (via
+/// [peek_str]) against mis-scanning the first `*` of a `**` power operator
+/// as multiplication: by the time `parse_product` is looking for its own
+/// operator, `parse_power` (the tighter-binding level) should already have
+/// consumed any `**` as part of parsing its operand, so seeing one here
+/// would mean the grammar let a power expression slip past unparsed; treat
+/// it as no product operator rather than greedily eating just the first `*`
+/// and leaving a stray `*` to be mis-parsed as the start of the next operand.
+///
+fn scan_product_operator(s: &str, context: ScanContext) -> ScanContext {
+    if peek_str(s, context, 2) == Some("**") {
+        return (false, context.1);
+    }
+    scan_operator(s, context, "*", "×")
+}
+
 ///
 /// Parse a series of multiplication operations.
 ///
-/// ```
+/// ```text
 /// product ::= quotient {['×' | '*']  quotient}*
 /// ```
 ///
-fn parse_product(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+fn parse_product(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
     const OPERATOR: &str = "*";
 
     //
     // skip any leading whitespace
     //
-    let (matched, start_position) = parse_whitespace(s, context)?;
+    let (matched, start_position) = parse_whitespace(s, context, options)?;
 
 
-    let ((matched, mut operand_position), left_node) = parse_quotient(s, (matched, start_position))?;
+    let ((matched, mut operand_position), left_node) = parse_quotient(s, (matched, start_position), options)?;
     let end_position = operand_position;
 
     //
     // scan operator
     //
-    let (mut matched, mut position) = scan_literal(s, parse_whitespace(s, (matched, operand_position))?, OPERATOR);
+    let mut operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+    let (mut matched, mut position) = scan_product_operator(s, (true, operator_position));
     if matched {
         //
         // collect up all operands.
@@ -470,18 +1024,31 @@ fn parse_product(s: &str, context: ScanContext) -> Result<(ScanContext, Expressi
         // - put it into the vector
         // - put the vector into an sum expression node
         //
-        let mut operands = vec!(left_node);
+        let mut operands = Vec::with_capacity(estimate_operand_count(s, start_position.byte_index, OPERATOR));
+        operands.push(left_node);
         while matched {
             let parse_node: ExpressionNode;
 
             // scan next operand
-            ((matched, operand_position), parse_node) = parse_quotient(s, (matched, position))?;
+            match parse_quotient(s, (matched, position), options) {
+                Ok(((new_matched, new_position), node)) => {
+                    matched = new_matched;
+                    operand_position = new_position;
+                    parse_node = node;
+                },
+                Err(ParsingError::EndOfInput(_)) => {
+                    return Err(ParsingError::MissingOperand(ParsePosition::new(&operator_position, &position), '*'));
+                },
+                Err(other) => return Err(other),
+            }
 
             // add it to the operands
             operands.push(parse_node);
+            check_operand_count(operands.len(), &ParsePosition::new(&start_position, &operand_position), options)?;
 
             // scan next operator
-            (matched, position) = scan_literal(s, parse_whitespace(s, (matched, operand_position))?, OPERATOR);
+            operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+            (matched, position) = scan_product_operator(s, (true, operator_position));
         }
 
         Ok(((true, operand_position), ExpressionNode::Product {
@@ -503,26 +1070,28 @@ fn parse_product(s: &str, context: ScanContext) -> Result<(ScanContext, Expressi
 ///
 /// Parse a series of division operations.
 ///
-/// ```
+/// ```text
 /// quotient ::= power {['÷' | '/'] power}*
 /// ```
 ///
-fn parse_quotient(s: &str, context: ScanContext) -> Result<(ScanContext, ExpressionNode), ParsingError> {
+fn parse_quotient(s: &str, context: ScanContext, options: &ParseOptions) -> Result<(ScanContext, ExpressionNode), ParsingError> {
     const OPERATOR: &str = "/";
+    const ALTERNATE_OPERATOR: &str = "÷";
 
     //
     // skip any leading whitespace
     //
-    let (matched, start_position) = parse_whitespace(s, context)?;
+    let (matched, start_position) = parse_whitespace(s, context, options)?;
 
 
-    let ((matched, mut operand_position), left_node) = parse_power(s, (matched, start_position))?;
+    let ((matched, mut operand_position), left_node) = parse_power(s, (matched, start_position), options)?;
     let end_position = operand_position;
 
     //
     // scan operator
     //
-    let (mut matched, mut position) = scan_literal(s, parse_whitespace(s, (matched, operand_position))?, OPERATOR);
+    let mut operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+    let (mut matched, mut position) = scan_operator(s, (true, operator_position), OPERATOR, ALTERNATE_OPERATOR);
     if matched {
         //
         // collect up all operands.
@@ -530,18 +1099,31 @@ fn parse_quotient(s: &str, context: ScanContext) -> Result<(ScanContext, Express
         // - put it into the vector
         // - put the vector into an sum expression node
         //
-        let mut operands = vec!(left_node);
+        let mut operands = Vec::with_capacity(estimate_operand_count(s, start_position.byte_index, OPERATOR));
+        operands.push(left_node);
         while matched {
             let parse_node: ExpressionNode;
 
             // scan next operand
-            ((matched, operand_position), parse_node) = parse_power(s, (matched, position))?;
+            match parse_power(s, (matched, position), options) {
+                Ok(((new_matched, new_position), node)) => {
+                    matched = new_matched;
+                    operand_position = new_position;
+                    parse_node = node;
+                },
+                Err(ParsingError::EndOfInput(_)) => {
+                    return Err(ParsingError::MissingOperand(ParsePosition::new(&operator_position, &position), '/'));
+                },
+                Err(other) => return Err(other),
+            }
 
             // add it to the operands
             operands.push(parse_node);
+            check_operand_count(operands.len(), &ParsePosition::new(&start_position, &operand_position), options)?;
 
             // scan next operator
-            (matched, position) = scan_literal(s, parse_whitespace(s, (matched, operand_position))?, OPERATOR);
+            operator_position = parse_whitespace(s, (matched, operand_position), options)?.1;
+            (matched, position) = scan_operator(s, (true, operator_position), OPERATOR, ALTERNATE_OPERATOR);
         }
 
         Ok(((true, operand_position), ExpressionNode::Quotient {
@@ -560,17 +1142,72 @@ fn parse_quotient(s: &str, context: ScanContext) -> Result<(ScanContext, Express
 
 #[cfg(test)]
 mod parse_tests {
-    use crate::expression::value::{DecimalType, IntegerType, SignType};
+    use crate::expression::value::{DecimalType, IntegerType, SignType, ExpressionValue};
+    use crate::expression::node::Evaluate;
 
     use super::*;
 
+    #[test]
+    fn test_parse_unmatched_closing_parenthesis_errors() {
+        let error = parse("1 + 2)", beginning(), &ParseOptions::default()).unwrap_err();
+        assert!(matches!(error, ParsingError::UnbalancedParenthesis(_)));
+        assert_eq!(5, error.position().start.char_index);
+    }
+
+    #[test]
+    fn test_parse_from_reader_cursor() {
+        let cursor = std::io::Cursor::new(b"1 + 2");
+        let node = parse_from_reader(cursor).unwrap();
+        assert_eq!(node.evaluate(), ExpressionValue::Integer { value: 3 });
+    }
+
+    #[test]
+    fn test_evaluate_str_parses_and_evaluates_in_one_call() {
+        assert_eq!(ExpressionValue::Integer { value: 7 }, evaluate_str("1 + 2*3").unwrap());
+    }
+
+    #[test]
+    fn test_evaluate_str_reports_a_parse_error() {
+        assert!(evaluate_str("1 +").is_err());
+    }
+
+    #[test]
+    fn test_from_rpn_builds_sum_evaluating_to_three() {
+        let node = from_rpn(&["1", "2", "+"]).unwrap();
+        assert!(matches!(node, ExpressionNode::Sum { .. }));
+        assert_eq!(ExpressionValue::Integer { value: 3 }, node.evaluate());
+    }
+
+    #[test]
+    fn test_from_rpn_too_few_operands_errors() {
+        assert!(matches!(from_rpn(&["1", "+"]), Err(ParsingError::MissingOperand(_, '+'))));
+    }
+
+    #[test]
+    fn test_from_rpn_leftover_tokens_errors() {
+        assert!(matches!(from_rpn(&["1", "2"]), Err(ParsingError::ExtraInput(_))));
+    }
+
+    #[test]
+    fn test_from_rpn_empty_tokens_errors() {
+        assert!(matches!(from_rpn(&[]), Err(ParsingError::EndOfInput(_))));
+    }
+
+    #[test]
+    fn test_from_rpn_handles_all_binary_operators() {
+        assert_eq!(ExpressionValue::Integer { value: -2 }, from_rpn(&["2", "4", "-"]).unwrap().evaluate());
+        assert_eq!(ExpressionValue::Integer { value: 8 }, from_rpn(&["2", "4", "*"]).unwrap().evaluate());
+        assert_eq!(ExpressionValue::Integer { value: 2 }, from_rpn(&["8", "4", "/"]).unwrap().evaluate());
+        assert_eq!(ExpressionValue::Integer { value: 16 }, from_rpn(&["2", "4", "^"]).unwrap().evaluate());
+    }
+
     #[test]
     fn test_parse_number_integer() {
         let s = "1234";
         let start = ScanPosition::default();
         let context = (true, start);
 
-        let (result_context, result_node) = parse_number(s, context).unwrap();
+        let (result_context, result_node) = parse_number(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Integer{
@@ -579,13 +1216,227 @@ mod parse_tests {
         }, result_node);
     }
 
+    #[test]
+    fn test_parse_number_leading_decimal_point() {
+        let s = ".5";
+        let start = ScanPosition::default();
+        let context = (true, start);
+
+        let (result_context, result_node) = parse_number(s, context, &ParseOptions::default()).unwrap();
+        let expected_end = ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0);
+        assert_eq!((true, expected_end), result_context);
+        assert_eq!(ExpressionNode::Decimal{
+            position: ParsePosition { start: start, end: expected_end },
+            value: 0.5 as DecimalType
+        }, result_node);
+    }
+
+    #[test]
+    fn test_parse_number_trailing_decimal_point() {
+        let s = "5.";
+        let start = ScanPosition::default();
+        let context = (true, start);
+
+        let (result_context, result_node) = parse_number(s, context, &ParseOptions::default()).unwrap();
+        let expected_end = ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0);
+        assert_eq!((true, expected_end), result_context);
+        assert_eq!(ExpressionNode::Decimal{
+            position: ParsePosition { start: start, end: expected_end },
+            value: 5 as DecimalType
+        }, result_node);
+    }
+
+    #[test]
+    fn test_parse_number_bare_decimal_point_errors() {
+        let s = ".";
+        let context = (true, ScanPosition::default());
+
+        assert!(parse_number(s, context, &ParseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_number_multiple_decimal_points_errors() {
+        let s = "1.2.3";
+        let context = (true, ScanPosition::default());
+
+        match parse_number(s, context, &ParseOptions::default()) {
+            Err(ParsingError::Number(_position, NumberError::MultipleDecimalPoints)) => {},
+            other => panic!("expected Number(MultipleDecimalPoints), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_number_exponent_without_digits_errors() {
+        let s = "1e";
+        let context = (true, ScanPosition::default());
+
+        match parse_number(s, context, &ParseOptions::default()) {
+            Err(ParsingError::Number(_position, NumberError::ExponentWithoutDigits)) => {},
+            other => panic!("expected Number(ExponentWithoutDigits), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_number_out_of_range_errors() {
+        let s = "99999999999";
+        let context = (true, ScanPosition::default());
+
+        match parse_number(s, context, &ParseOptions::default()) {
+            Err(ParsingError::Number(_position, NumberError::OutOfRange(Some(_source)))) => {},
+            other => panic!("expected Number(OutOfRange(Some(_))), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_number_out_of_range_error_source_chains_to_parse_error() {
+        let s = "99999999999";
+        let context = (true, ScanPosition::default());
+
+        let error = parse_number(s, context, &ParseOptions::default()).expect_err("expected an error");
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn test_parse_number_out_of_range_error_can_be_boxed_as_std_error() {
+        let s = "99999999999";
+        let context = (true, ScanPosition::default());
+
+        let error = parse_number(s, context, &ParseOptions::default()).expect_err("expected an error");
+        let boxed: Box<dyn std::error::Error> = Box::new(error);
+        assert!(boxed.source().is_some());
+    }
+
+    #[test]
+    fn test_parsing_error_without_source_can_be_boxed_as_std_error() {
+        let error = ParsingError::EndOfInput(ParsePosition::default());
+        let boxed: Box<dyn std::error::Error> = Box::new(error);
+        assert!(boxed.source().is_none());
+    }
+
+    // `parse_number` returns a `ParsingError` on a numeric conversion failure
+    // (e.g. an `i32` literal too large to fit) instead of printing to stdout
+    // and returning it; an out-of-range literal exercising the `i32::parse`
+    // failure path (as opposed to `check_magnitude`'s separate, pre-parse
+    // bound) should still just be an `Err`, with no stray output.
+    #[test]
+    fn test_parse_number_integer_conversion_failure_returns_error_without_printing() {
+        let s = "999999999999999999999999999999";
+        let context = (true, ScanPosition::default());
+
+        match parse_number(s, context, &ParseOptions::default()) {
+            Err(ParsingError::Number(_position, NumberError::OutOfRange(Some(_source)))) => {},
+            other => panic!("expected Number(OutOfRange(Some(_))), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_number_exceeding_max_abs_value_errors() {
+        let s = "1e400";
+        let context = (true, ScanPosition::default());
+        let options = ParseOptions { max_abs_value: Some(1e100), ..Default::default() };
+
+        match parse_number(s, context, &options) {
+            Err(ParsingError::Number(_position, NumberError::OutOfRange(None))) => {},
+            other => panic!("expected Number(OutOfRange(None)), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_number_within_max_abs_value_passes() {
+        let s = "123.5";
+        let context = (true, ScanPosition::default());
+        let options = ParseOptions { max_abs_value: Some(1e100), ..Default::default() };
+
+        let (_result_context, result_node) = parse_number(s, context, &options).unwrap();
+        assert_eq!(ExpressionNode::Decimal {
+            position: ParsePosition::new(&ScanPosition::default(), &ScanPosition::new(5, 5, 0, 0, 0)),
+            value: 123.5 as DecimalType
+        }, result_node);
+    }
+
+    #[test]
+    fn test_parse_sum_exceeding_max_operands_errors() {
+        let s = "1+2+3+4+5+6+7+8+9+10";
+        let options = ParseOptions { max_operands: Some(5), ..Default::default() };
+
+        match parse_sum(s, beginning(), &options) {
+            Err(ParsingError::TooManyOperands(_position)) => {},
+            other => panic!("expected TooManyOperands, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_sum_within_max_operands_passes() {
+        let s = "1+2+3";
+        let options = ParseOptions { max_operands: Some(5), ..Default::default() };
+
+        let (_result_context, result_node) = parse_sum(s, beginning(), &options).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 6 }, result_node.evaluate());
+    }
+
+    #[test]
+    fn test_parse_product_accepts_unicode_multiplication_sign() {
+        let s = "3 × 4";
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 12 }, result_node.evaluate());
+    }
+
+    #[test]
+    fn test_parse_quotient_accepts_unicode_division_sign() {
+        let s = "6 ÷ 2";
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 3 }, result_node.evaluate());
+    }
+
+    #[test]
+    fn test_parse_expression_mixes_unicode_and_ascii_product_and_quotient_operators() {
+        // product ::= quotient {['×' | '*'] quotient}*, so each quotient group
+        // (4 ÷ 2, then 3 / 3) binds tighter than the '×'/'*' that joins it to
+        // the rest: 6 × (4 ÷ 2) * (3 / 3) == 6 * 2 * 1 == 12
+        let s = "6 × 4 ÷ 2 * 3 / 3";
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 12 }, result_node.evaluate());
+    }
+
+    #[test]
+    fn test_scan_number_value_integer() {
+        let s = "42";
+        let context = (true, ScanPosition::default());
+
+        let (result_context, result_value) = scan_number_value(s, context, &ParseOptions::default());
+        let expected_end = ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0);
+        assert_eq!((true, expected_end), result_context);
+        assert_eq!(Some(ExpressionValue::Integer { value: 42 }), result_value);
+    }
+
+    #[test]
+    fn test_scan_number_value_decimal() {
+        let s = "3.14";
+        let context = (true, ScanPosition::default());
+
+        let (result_context, result_value) = scan_number_value(s, context, &ParseOptions::default());
+        let expected_end = ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0);
+        assert_eq!((true, expected_end), result_context);
+        assert_eq!(Some(ExpressionValue::Decimal { value: 3.14 }), result_value);
+    }
+
+    #[test]
+    fn test_scan_number_value_no_match() {
+        let s = "abc";
+        let context = (true, ScanPosition::default());
+
+        let (result_context, result_value) = scan_number_value(s, context, &ParseOptions::default());
+        assert_eq!(context, result_context);
+        assert_eq!(None, result_value);
+    }
+
     #[test]
     fn test_parse_number_decimal() {
         let s = "1234.0";
         let start = ScanPosition::default();
         let context = (true, start);
 
-        let (result_context, result_node) = parse_number(s, context).unwrap();
+        let (result_context, result_node) = parse_number(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Decimal{
@@ -600,7 +1451,7 @@ mod parse_tests {
         let start = ScanPosition::default();
         let context = (true, start);
 
-        let (result_context, result_node) = parse_number(s, context).unwrap();
+        let (result_context, result_node) = parse_number(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Decimal{
@@ -612,7 +1463,7 @@ mod parse_tests {
         let start = ScanPosition::default();
         let context = (true, start);
 
-        let (result_context, result_node) = parse_number(s, context).unwrap();
+        let (result_context, result_node) = parse_number(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Decimal{
@@ -627,7 +1478,7 @@ mod parse_tests {
         let start = ScanPosition::new(1, 1, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_value(s, context).unwrap();
+        let (result_context, result_node) = parse_value(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Parenthesis{
@@ -652,7 +1503,7 @@ mod parse_tests {
         let start = ScanPosition::new(1, 1, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_value(s, context).unwrap();
+        let (result_context, result_node) = parse_value(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Parenthesis{
@@ -677,7 +1528,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_value(s, context).unwrap();
+        let (result_context, result_node) = parse_value(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len()- 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Parenthesis{
@@ -702,7 +1553,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_value(s, context).unwrap();
+        let (result_context, result_node) = parse_value(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Parenthesis{
@@ -726,7 +1577,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_value(s, context).unwrap();
+        let (result_context, result_node) = parse_value(s, context, &ParseOptions::default()).unwrap();
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
         assert_eq!(ExpressionNode::Parenthesis{
@@ -758,7 +1609,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_sum(s, context).unwrap();
+        let (result_context, result_node) = parse_sum(s, context, &ParseOptions::default()).unwrap();
         println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -792,7 +1643,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_sum(s, context).unwrap();
+        let (result_context, result_node) = parse_sum(s, context, &ParseOptions::default()).unwrap();
         // println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -882,13 +1733,101 @@ mod parse_tests {
         }, result_node);
     }
 
+    #[test]
+    fn test_parse_sum_many_terms() {
+        // confirm the with_capacity lookahead in parse_sum doesn't change behavior
+        // for a long chain of operands, and still produces a flat operand vector.
+        const TERM_COUNT: usize = 500;
+        let s = (0..TERM_COUNT).map(|_| "1").collect::<Vec<_>>().join(" + ");
+
+        let start = ScanPosition::new(0, 0, 0, 0, 0);
+        let context = (true, start);
+
+        let (_result_context, result_node) = parse_sum(&s, context, &ParseOptions::default()).unwrap();
+        match result_node {
+            ExpressionNode::Sum { position: _, operands } => {
+                assert_eq!(TERM_COUNT, operands.len());
+                for operand in &operands {
+                    assert_eq!(ExpressionValue::Integer { value: 1 }, operand.evaluate());
+                }
+            },
+            other => panic!("expected Sum, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_sum_comma_as_whitespace() {
+        let options = ParseOptions { extra_whitespace: vec!(','), ..Default::default() };
+
+        // a comma before the operator is simply invisible once configured as whitespace
+        let (_result_context, result_node) = parse(" 1 ,+ 2 ", beginning(), &options).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 3 }, result_node.evaluate());
+    }
+
+    #[test]
+    fn test_parse_sum_non_breaking_space_errors_in_ascii_mode() {
+        let s = "1\u{00A0}+\u{00A0}2";
+
+        assert!(parse(s, beginning(), &ParseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_sum_non_breaking_space_succeeds_in_unicode_whitespace_mode() {
+        let options = ParseOptions { unicode_whitespace: true, ..Default::default() };
+        let s = "1\u{00A0}+\u{00A0}2";
+
+        let (_result_context, result_node) = parse(s, beginning(), &options).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 3 }, result_node.evaluate());
+    }
+
+    #[test]
+    fn test_parse_sum_missing_right_operand_errors() {
+        let result = parse("1 +", beginning(), &ParseOptions::default());
+        match result {
+            Err(ParsingError::MissingOperand(_position, '+')) => {},
+            other => panic!("expected MissingOperand('+'), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_product_missing_right_operand_errors() {
+        let result = parse("2 *", beginning(), &ParseOptions::default());
+        match result {
+            Err(ParsingError::MissingOperand(_position, '*')) => {},
+            other => panic!("expected MissingOperand('*'), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_difference_missing_right_operand_errors() {
+        let result = parse("3 -", beginning(), &ParseOptions::default());
+        match result {
+            Err(ParsingError::MissingOperand(_position, '-')) => {},
+            other => panic!("expected MissingOperand('-'), got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_unclosed_parenthesis_auto_closes_in_lenient_mode() {
+        let options = ParseOptions { auto_close_parens: true, ..Default::default() };
+
+        let (_result_context, result_node) = parse("(1 + 2", beginning(), &options).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 3 }, result_node.evaluate());
+    }
+
+    #[test]
+    fn test_parse_unclosed_parenthesis_errors_in_strict_mode() {
+        let result = parse("(1 + 2", beginning(), &ParseOptions::default());
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_difference() {
         let s = " 2 - 3 ";
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_difference(s, context).unwrap();
+        let (result_context, result_node) = parse_difference(s, context, &ParseOptions::default()).unwrap();
         println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -922,7 +1861,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_difference(s, context).unwrap();
+        let (result_context, result_node) = parse_difference(s, context, &ParseOptions::default()).unwrap();
         // println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -1018,7 +1957,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_product(s, context).unwrap();
+        let (result_context, result_node) = parse_product(s, context, &ParseOptions::default()).unwrap();
         println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -1053,7 +1992,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_quotient(s, context).unwrap();
+        let (result_context, result_node) = parse_quotient(s, context, &ParseOptions::default()).unwrap();
         println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -1088,7 +2027,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_power(s, context).unwrap();
+        let (result_context, result_node) = parse_power(s, context, &ParseOptions::default()).unwrap();
         println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -1114,13 +2053,56 @@ mod parse_tests {
         }, result_node);
     }
 
+    #[test]
+    fn test_parse_power_accepts_double_star_alias() {
+        let s = "2 ** 3";
+
+        let (_result_context, result_node) = parse_power(s, beginning(), &ParseOptions::default()).unwrap();
+        assert!(matches!(result_node, ExpressionNode::Power { .. }));
+        assert_eq!(ExpressionValue::Integer { value: 8 }, result_node.evaluate());
+    }
+
+    #[test]
+    fn test_parse_product_double_star_operand_is_a_single_power_not_two_products() {
+        let s = "2 * 3 ** 4";
+
+        let (_result_context, result_node) = parse_product(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 2 * (3_i32.pow(4)) }, result_node.evaluate());
+        match result_node {
+            ExpressionNode::Product { position: _, operands } => {
+                assert_eq!(2, operands.len());
+                assert!(matches!(operands[1], ExpressionNode::Power { .. }));
+            },
+            other => panic!("expected Product, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_product_lone_double_star_without_left_operand_errors() {
+        let s = "* *3";
+
+        assert!(parse_product(s, beginning(), &ParseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_product_three_operands_still_parses_as_single_product() {
+        let s = "2 * 3 * 4";
+
+        let (_result_context, result_node) = parse_product(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionValue::Integer { value: 24 }, result_node.evaluate());
+        match result_node {
+            ExpressionNode::Product { position: _, operands } => assert_eq!(3, operands.len()),
+            other => panic!("expected Product, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_power_complex() {
         let s = " (0.0+2)^(1.0+2) ";
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_power(s, context).unwrap();
+        let (result_context, result_node) = parse_power(s, context, &ParseOptions::default()).unwrap();
         println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -1196,7 +2178,7 @@ mod parse_tests {
         let start = ScanPosition::new(0, 0, 0, 0, 0);
         let context = (true, start);
 
-        let (result_context, result_node) = parse_expression(s, context).unwrap();
+        let (result_context, result_node) = parse_expression(s, context, &ParseOptions::default()).unwrap();
         // println!("{:?}", result_node);
         let expected_end = ScanPosition::new(s.len() - 1, s.chars().count() - 1, 0, 0, 0);
         assert_eq!((true, expected_end), result_context);
@@ -1289,7 +2271,7 @@ mod parse_tests {
 }
 #[cfg(test)]
 mod evaluation_tests {
-    use crate::{expression::{value::{DecimalType, ExpressionValue}, node::Evaluate}, scan::context::beginning};
+    use crate::{expression::{value::{DecimalType, ExpressionValue}, error::EvaluationError, node::{Evaluate, Position}}, scan::context::beginning};
 
     use super::*;
 
@@ -1297,8 +2279,8 @@ mod evaluation_tests {
     fn test_evaluate_integer() {
         let s = "1234";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 1234 });
     }
 
@@ -1306,8 +2288,8 @@ mod evaluation_tests {
     fn test_evaluate_negative_integer() {
         let s = "-1234";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: -1234 });
     }
 
@@ -1315,8 +2297,8 @@ mod evaluation_tests {
     fn test_evaluate_decimal() {
         let s = "1234.0";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 1234 as DecimalType });
     }
 
@@ -1324,8 +2306,8 @@ mod evaluation_tests {
     fn test_evaluate_negative_decimal() {
         let s = "-1234.0";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: -1234 as DecimalType });
     }
 
@@ -1333,8 +2315,8 @@ mod evaluation_tests {
     fn test_evaluate_scientific() {
         let s = "1234e0";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 1234 as DecimalType });
     }
 
@@ -1342,17 +2324,35 @@ mod evaluation_tests {
     fn test_evaluate_negative_scientific() {
         let s = "-1234E0";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: -1234 as DecimalType });
     }
 
+    #[test]
+    fn test_evaluate_scientific_with_negative_exponent() {
+        let s = "1234e-2";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 12.34 as DecimalType });
+    }
+
+    #[test]
+    fn test_evaluate_scientific_with_positive_exponent() {
+        let s = "12.34e+2";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 1234 as DecimalType });
+    }
+
     #[test]
     fn test_evaluate_parenthesis() {
         let s = "(1234)";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 1234 });
     }
 
@@ -1360,8 +2360,8 @@ mod evaluation_tests {
     fn test_evaluate_negative_parenthesis() {
         let s = "-(1234)";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: -1234 });
     }
 
@@ -1369,8 +2369,8 @@ mod evaluation_tests {
     fn test_evaluate_integer_sum() {
         let s = " 1 + 2 + 3 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 6 });
     }
 
@@ -1378,8 +2378,8 @@ mod evaluation_tests {
     fn test_evaluate_decimal_sum() {
         let s = " 1 + 2 + 3.0 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 6 as DecimalType });
     }
 
@@ -1387,8 +2387,8 @@ mod evaluation_tests {
     fn test_evaluate_integer_difference() {
         let s = " 1 - 2 - 3 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: -4 });
     }
 
@@ -1396,8 +2396,8 @@ mod evaluation_tests {
     fn test_evaluate_decimal_difference() {
         let s = " 1 - 2 - 3.0 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: -4 as DecimalType });
     }
 
@@ -1405,8 +2405,8 @@ mod evaluation_tests {
     fn test_evaluate_integer_product() {
         let s = " 1 * 2 * 3 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 6 });
     }
 
@@ -1414,8 +2414,8 @@ mod evaluation_tests {
     fn test_evaluate_decimal_product() {
         let s = " 1 * 2 * 3.0 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 6 as DecimalType });
     }
 
@@ -1423,93 +2423,148 @@ mod evaluation_tests {
     fn test_evaluate_integer_quotient() {
         let s = " 3 / 2 / 1";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
-        assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 1 });
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Rational { numerator: 3, denominator: 2 });
     }
 
     #[test]
     fn test_evaluate_decimal_quotient() {
         let s = " 3.0 / 2 / 1.0 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 1.5 as DecimalType });
     }
 
     #[test]
     fn test_evaluate_divide_by_zero() {
         let s = " 3 / 0 / 1 ";
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
-        assert_eq!(result_node.evaluate(), ExpressionValue::NaN);
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) });
 
         let s = " 3.0 / 0.0 / 1.0 ";
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
-        assert_eq!(result_node.evaluate(), ExpressionValue::NaN);
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) });
     }
 
     #[test]
     fn test_evaluate_integer_power() {
         let s = " 3^2";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 9 });
 
         let s = " 3^0";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 1 });
 
+        // a negative integer exponent produces a fractional result, which must
+        // become an exact Rational rather than truncate to 0 (see test_evaluate_negative_integer_exponent_produces_rational)
         let s = " 3^-1";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
-        assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 0 });
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Rational { numerator: 1, denominator: 3 });
+    }
+
+    #[test]
+    fn test_evaluate_negative_integer_exponent_produces_rational() {
+        let s = "2^-3";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionNode::Power {
+            position: result_node.position(),
+            base: Box::new(ExpressionNode::Integer { position: ParsePosition::new(&ScanPosition::default(), &ScanPosition::new(1, 1, 0, 0, 0)), value: 2 }),
+            exponent: Box::new(ExpressionNode::Integer { position: ParsePosition::new(&ScanPosition::new(2, 2, 0, 0, 0), &ScanPosition::new(4, 4, 0, 0, 0)), value: -3 }),
+        }, result_node);
+        assert_eq!(result_node.evaluate(), ExpressionValue::Rational { numerator: 1, denominator: 8 });
+    }
+
+    #[test]
+    fn test_evaluate_parenthesized_exponent() {
+        let s = "2^(1+2)";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionNode::Power {
+            position: result_node.position(),
+            base: Box::new(ExpressionNode::Integer { position: ParsePosition::new(&ScanPosition::default(), &ScanPosition::new(1, 1, 0, 0, 0)), value: 2 }),
+            exponent: Box::new(ExpressionNode::Parenthesis {
+                position: ParsePosition::new(&ScanPosition::new(2, 2, 0, 0, 0), &ScanPosition::new(7, 7, 0, 0, 0)),
+                sign: SignType::Positive,
+                inner: Box::new(ExpressionNode::Sum {
+                    position: ParsePosition::new(&ScanPosition::new(3, 3, 0, 0, 0), &ScanPosition::new(6, 6, 0, 0, 0)),
+                    operands: vec!(
+                        ExpressionNode::Integer { position: ParsePosition::new(&ScanPosition::new(3, 3, 0, 0, 0), &ScanPosition::new(4, 4, 0, 0, 0)), value: 1 },
+                        ExpressionNode::Integer { position: ParsePosition::new(&ScanPosition::new(5, 5, 0, 0, 0), &ScanPosition::new(6, 6, 0, 0, 0)), value: 2 },
+                    ),
+                }),
+            }),
+        }, result_node);
+        assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: 8 });
     }
 
     #[test]
     fn test_evaluate_decimal_power() {
         let s = " 3.0^2 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 9 as DecimalType });
 
         let s = " 3.0^2.0 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 9 as DecimalType });
 
         let s = " 3^2.0 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 9 as DecimalType });
 
         let s = " 3.0^0 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 1 as DecimalType });
 
         let s = " 2.0^-1 ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 0.5 as DecimalType });
     }
 
+    #[test]
+    fn test_evaluate_integer_power_overflow_yields_nan_but_decimal_power_does_not() {
+        let s = "2^40";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) });
+
+        let s = "2.0^40";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 2f64.powf(40.0) });
+    }
+
     #[test]
     fn test_evaluate_integer_expression() {
         let s = " (((10 + 5) * -6) - -20 / -2 * 3 + -((5*2)^2) - (-5 * -2 * 5)) ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Integer { value: -270 });
     }
 
@@ -1517,8 +2572,171 @@ mod evaluation_tests {
     fn test_evaluate_decimal_expression() {
         let s = " (((10 + 5) * -6) - -20.0 / -2 * 3 + -((5*2)^2) - (-5 * -2 * 5)) ";
 
-        print_expression_result(s, beginning());
-        let (_result_context, result_node) = parse_expression(s, beginning()).unwrap();
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
         assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: -270 as DecimalType});
     }
+
+    #[test]
+    fn test_evaluate_radical() {
+        let s = "√4";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 2 as DecimalType });
+    }
+
+    #[test]
+    fn test_evaluate_radical_of_parenthesis() {
+        let s = "√(9)";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 3 as DecimalType });
+    }
+
+    #[test]
+    fn test_evaluate_identifier_variable_is_unbound() {
+        let s = "x";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionNode::Variable { position: result_node.position(), name: "x".to_string() }, result_node);
+        assert_eq!(result_node.evaluate(), ExpressionValue::NaN { reason: None });
+    }
+
+    #[test]
+    fn test_evaluate_with_bindings_reused_across_evaluations() {
+        let s = "x * 2 + y";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+
+        let mut bindings = std::collections::HashMap::new();
+        bindings.insert("x".to_string(), ExpressionValue::Integer { value: 3 });
+        bindings.insert("y".to_string(), ExpressionValue::Integer { value: 4 });
+        assert_eq!(ExpressionValue::Integer { value: 10 }, result_node.evaluate_with(&bindings));
+
+        bindings.insert("x".to_string(), ExpressionValue::Integer { value: 5 });
+        assert_eq!(ExpressionValue::Integer { value: 14 }, result_node.evaluate_with(&bindings));
+    }
+
+    #[test]
+    fn test_evaluate_identifier_pi_is_the_constant() {
+        let s = "pi";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(ExpressionNode::Constant { position: result_node.position(), name: "pi".to_string() }, result_node);
+        match result_node.evaluate() {
+            ExpressionValue::Decimal { value } => assert!((value - std::f64::consts::PI).abs() < 1e-9, "expected approximately PI, got {}", value),
+            other => panic!("expected Decimal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_product_of_two_and_pi() {
+        let s = "2 * pi";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        match result_node.evaluate() {
+            ExpressionValue::Decimal { value } => assert!((value - 2.0 * std::f64::consts::PI).abs() < 1e-9, "expected approximately 2*PI, got {}", value),
+            other => panic!("expected Decimal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_e_raised_to_the_first_power() {
+        let s = "e^1";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        match result_node.evaluate() {
+            ExpressionValue::Decimal { value } => assert!((value - std::f64::consts::E).abs() < 1e-9, "expected approximately E, got {}", value),
+            other => panic!("expected Decimal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_identifier_function_call() {
+        let s = "sqrt(4)";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 2 as DecimalType });
+    }
+
+    #[test]
+    fn test_evaluate_nested_function_composition() {
+        let s = "sqrt(abs(-16))";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 4 as DecimalType });
+    }
+
+    #[test]
+    fn test_evaluate_sqrt_of_sixteen() {
+        let s = "sqrt(16)";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 4 as DecimalType });
+    }
+
+    #[test]
+    fn test_evaluate_abs_of_negative_five() {
+        let s = "abs(-5)";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 5 as DecimalType });
+    }
+
+    #[test]
+    fn test_parse_unknown_function_name_errors_at_the_call_position() {
+        let s = "bogus(4)";
+
+        match parse_expression(s, beginning(), &ParseOptions::default()) {
+            Err(ParsingError::UnknownFunction(position, name)) => {
+                assert_eq!("bogus", name);
+                assert_eq!(ParsePosition::new(&ScanPosition::default(), &ScanPosition::new(8, 8, 0, 0, 0)), position);
+            },
+            other => panic!("expected UnknownFunction, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_floor_and_ceil() {
+        let s = "floor(3.7) + ceil(3.2)";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        assert_eq!(result_node.evaluate(), ExpressionValue::Decimal { value: 7 as DecimalType });
+    }
+
+    #[test]
+    fn test_evaluate_degrees() {
+        let s = "180°";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        match result_node.evaluate() {
+            ExpressionValue::Decimal { value } => assert!((value - std::f64::consts::PI).abs() < 1e-9, "expected approximately PI, got {}", value),
+            other => panic!("expected Decimal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_evaluate_degrees_of_parenthesis() {
+        let s = "(90)°";
+
+        print_expression_result(s, beginning(), &ParseOptions::default());
+        let (_result_context, result_node) = parse_expression(s, beginning(), &ParseOptions::default()).unwrap();
+        match result_node.evaluate() {
+            ExpressionValue::Decimal { value } => assert!((value - std::f64::consts::PI / 2.0).abs() < 1e-9, "expected approximately PI/2, got {}", value),
+            other => panic!("expected Decimal, got {:?}", other),
+        }
+    }
 }
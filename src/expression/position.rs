@@ -16,4 +16,78 @@ impl ParsePosition {
             end: *end
         }
     }
+
+    ///
+    /// Compute the minimal span that covers both `self` and `other`:
+    /// the earliest of the two starts and the latest of the two ends.
+    ///
+    pub fn merge(&self, other: &ParsePosition) -> ParsePosition {
+        let start = if other.start.byte_index < self.start.byte_index { other.start } else { self.start };
+        let end = if other.end.byte_index > self.end.byte_index { other.end } else { self.end };
+        ParsePosition { start, end }
+    }
+
+    ///
+    /// True if `start` doesn't come after `end`, as it should for any
+    /// position that actually spans a piece of source text.
+    ///
+    pub fn is_valid(&self) -> bool {
+        self.start.byte_index <= self.end.byte_index
+    }
+}
+
+///
+/// A [ParsePosition] whose `start` comes after its `end`, rejected by a
+/// checked constructor like `ExpressionNode::integer`.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionError {
+    pub position: ParsePosition,
+}
+impl std::fmt::Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Invalid position {:?}: start is after end", &self.position))
+    }
+}
+impl std::error::Error for PositionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_zeroed_start_and_end() {
+        assert_eq!(ParsePosition::new(&ScanPosition::default(), &ScanPosition::default()), ParsePosition::default());
+    }
+
+    #[test]
+    fn test_is_valid_accepts_start_before_or_equal_to_end() {
+        assert!(ParsePosition::new(&ScanPosition::new(0, 0, 0, 0, 0), &ScanPosition::new(3, 3, 0, 0, 0)).is_valid());
+        assert!(ParsePosition::new(&ScanPosition::new(3, 3, 0, 0, 0), &ScanPosition::new(3, 3, 0, 0, 0)).is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_rejects_start_after_end() {
+        assert!(!ParsePosition::new(&ScanPosition::new(3, 3, 0, 0, 0), &ScanPosition::new(0, 0, 0, 0, 0)).is_valid());
+    }
+
+    #[test]
+    fn test_merge_disjoint_spans() {
+        let a = ParsePosition::new(&ScanPosition::new(0, 0, 0, 0, 0), &ScanPosition::new(3, 3, 0, 0, 0));
+        let b = ParsePosition::new(&ScanPosition::new(10, 10, 0, 0, 0), &ScanPosition::new(15, 15, 0, 0, 0));
+
+        let expected = ParsePosition::new(&ScanPosition::new(0, 0, 0, 0, 0), &ScanPosition::new(15, 15, 0, 0, 0));
+        assert_eq!(expected, a.merge(&b));
+        assert_eq!(expected, b.merge(&a));
+    }
+
+    #[test]
+    fn test_merge_overlapping_spans() {
+        let a = ParsePosition::new(&ScanPosition::new(0, 0, 0, 0, 0), &ScanPosition::new(8, 8, 0, 0, 0));
+        let b = ParsePosition::new(&ScanPosition::new(5, 5, 0, 0, 0), &ScanPosition::new(12, 12, 0, 0, 0));
+
+        let expected = ParsePosition::new(&ScanPosition::new(0, 0, 0, 0, 0), &ScanPosition::new(12, 12, 0, 0, 0));
+        assert_eq!(expected, a.merge(&b));
+        assert_eq!(expected, b.merge(&a));
+    }
 }
@@ -0,0 +1,328 @@
+//!
+//! A token-based parser that builds an [ExpressionNode] directly from a
+//! pre-scanned token vector instead of re-scanning a string, useful for
+//! incremental use cases (e.g. re-parsing as a user types) where the
+//! tokens are already available and re-scanning the whole string on every
+//! keystroke would be wasteful.
+//!
+//! This follows the same precedence-climbing grammar as [parse](super::parse)
+//! (`sum -> difference -> product -> quotient -> power -> value`), but walks
+//! a `&[Token]` with an index cursor instead of scanning `&str` with a
+//! `ScanContext`.
+//!
+//! NOTE: tokens don't carry source positions, so every [ExpressionNode]
+//!       built by [parse_tokens] has a default [ParsePosition]; callers
+//!       that need positions (e.g. to underline an error in the original
+//!       source) should use [parse](super::parse) instead.
+//!
+use super::error::{ParsingError, NumberError, NumberParseError};
+use super::node::ExpressionNode;
+use super::position::ParsePosition;
+use super::value::{DecimalType, IntegerType};
+use crate::scan::context::{ScanContext, scan_literal, scan_zero_or_more_chars, scan_n_chars, beginning};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Integer(IntegerType),
+    Decimal(DecimalType),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LeftParen,
+    RightParen,
+}
+
+///
+/// Split `s` into [Token]s, skipping whitespace.
+///
+/// NOTE: supports integers, decimals with a `.`, the ASCII operators
+///       `+ - * / ^` and parentheses. The rest of [parse](super::parse)'s
+///       syntax (scientific notation, `×`/`÷`, `**`, comparisons, the `√`
+///       radical, `°`, identifiers, constants and function calls) is not
+///       supported.
+///
+pub fn tokenize(s: &str) -> Result<Vec<Token>, ParsingError> {
+    token_iter(s).collect()
+}
+
+///
+/// Lazily scans `s` into [Token]s, one per [Iterator::next] call, instead of
+/// eagerly building a `Vec` like [tokenize] does — useful for a large
+/// document where a caller might not need every token (e.g. it bails out of
+/// a syntax check partway through).
+///
+/// Carries its own [ScanContext] cursor; construct with [token_iter].
+/// Once a call to `next()` returns `None` or `Some(Err(_))`, every
+/// subsequent call returns `None`.
+///
+pub struct TokenIter<'a> {
+    s: &'a str,
+    context: ScanContext,
+    done: bool,
+}
+
+///
+/// Create a [TokenIter] that lazily scans `s` into [Token]s.
+///
+pub fn token_iter(s: &str) -> TokenIter<'_> {
+    TokenIter { s, context: beginning(), done: false }
+}
+
+impl<'a> Iterator for TokenIter<'a> {
+    type Item = Result<Token, ParsingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let (_matched, position) = scan_zero_or_more_chars(self.s, self.context, |ch| ch.is_whitespace());
+        self.context = (true, position);
+        let Some(ch) = self.s[position.byte_index..].chars().next() else {
+            self.done = true;
+            return None;
+        };
+
+        let result = match ch {
+            '+' => Ok(self.scan_single_char_token(Token::Plus)),
+            '-' => Ok(self.scan_single_char_token(Token::Minus)),
+            '*' => Ok(self.scan_single_char_token(Token::Star)),
+            '/' => Ok(self.scan_single_char_token(Token::Slash)),
+            '^' => Ok(self.scan_single_char_token(Token::Caret)),
+            '(' => Ok(self.scan_single_char_token(Token::LeftParen)),
+            ')' => Ok(self.scan_single_char_token(Token::RightParen)),
+            _ if ch.is_ascii_digit() || ch == '.' => self.scan_number_token(),
+            _ => Err(ParsingError::Unknown(ParsePosition::default())),
+        };
+
+        if result.is_err() {
+            self.done = true;
+        }
+        Some(result)
+    }
+}
+
+impl<'a> TokenIter<'a> {
+    fn scan_single_char_token(&mut self, token: Token) -> Token {
+        let (_matched, position) = scan_n_chars(self.s, self.context, 1, |_ch| true);
+        self.context = (true, position);
+        token
+    }
+
+    fn scan_number_token(&mut self) -> Result<Token, ParsingError> {
+        let start_position = self.context.1;
+
+        let (_matched, after_digits) = scan_zero_or_more_chars(self.s, self.context, |ch| ch.is_ascii_digit());
+        let (has_dot, after_dot) = scan_literal(self.s, (true, after_digits), ".");
+        let end_position = if has_dot {
+            scan_zero_or_more_chars(self.s, (true, after_dot), |ch| ch.is_ascii_digit()).1
+        } else {
+            after_digits
+        };
+        self.context = (true, end_position);
+
+        let text = &self.s[start_position.byte_index..end_position.byte_index];
+        if has_dot {
+            text.parse::<DecimalType>().map(Token::Decimal).map_err(|_| ParsingError::Number(ParsePosition::default(), NumberError::NoDigits))
+        } else {
+            text.parse::<IntegerType>().map(Token::Integer).map_err(|err| ParsingError::Number(ParsePosition::default(), NumberError::OutOfRange(Some(NumberParseError::new(err)))))
+        }
+    }
+}
+
+///
+/// Build an [ExpressionNode] from a token vector produced by [tokenize].
+///
+/// ```text
+/// expression ::= sum
+/// ```
+///
+pub fn parse_tokens(tokens: &[Token]) -> Result<ExpressionNode, ParsingError> {
+    let (node, end) = parse_sum(tokens, 0)?;
+    if end != tokens.len() {
+        return Err(ParsingError::ExtraInput(ParsePosition::default()));
+    }
+    Ok(node)
+}
+
+///
+/// ```text
+/// sum ::= difference {'+' difference}*
+/// ```
+///
+fn parse_sum(tokens: &[Token], pos: usize) -> Result<(ExpressionNode, usize), ParsingError> {
+    let (left, mut pos) = parse_difference(tokens, pos)?;
+    if tokens.get(pos) != Some(&Token::Plus) {
+        return Ok((left, pos));
+    }
+
+    let mut addends = vec!(left);
+    while tokens.get(pos) == Some(&Token::Plus) {
+        let (addend, next) = parse_difference(tokens, pos + 1)?;
+        addends.push(addend);
+        pos = next;
+    }
+    Ok((ExpressionNode::Sum { position: ParsePosition::default(), operands: addends }, pos))
+}
+
+///
+/// ```text
+/// difference ::= product {'-' product}*
+/// ```
+///
+fn parse_difference(tokens: &[Token], pos: usize) -> Result<(ExpressionNode, usize), ParsingError> {
+    let (left, mut pos) = parse_product(tokens, pos)?;
+    if tokens.get(pos) != Some(&Token::Minus) {
+        return Ok((left, pos));
+    }
+
+    let mut operands = vec!(left);
+    while tokens.get(pos) == Some(&Token::Minus) {
+        let (operand, next) = parse_product(tokens, pos + 1)?;
+        operands.push(operand);
+        pos = next;
+    }
+    Ok((ExpressionNode::Difference { position: ParsePosition::default(), operands }, pos))
+}
+
+///
+/// ```text
+/// product ::= quotient {'*' quotient}*
+/// ```
+///
+fn parse_product(tokens: &[Token], pos: usize) -> Result<(ExpressionNode, usize), ParsingError> {
+    let (left, mut pos) = parse_quotient(tokens, pos)?;
+    if tokens.get(pos) != Some(&Token::Star) {
+        return Ok((left, pos));
+    }
+
+    let mut operands = vec!(left);
+    while tokens.get(pos) == Some(&Token::Star) {
+        let (operand, next) = parse_quotient(tokens, pos + 1)?;
+        operands.push(operand);
+        pos = next;
+    }
+    Ok((ExpressionNode::Product { position: ParsePosition::default(), operands }, pos))
+}
+
+///
+/// ```text
+/// quotient ::= power {'/' power}*
+/// ```
+///
+fn parse_quotient(tokens: &[Token], pos: usize) -> Result<(ExpressionNode, usize), ParsingError> {
+    let (left, mut pos) = parse_power(tokens, pos)?;
+    if tokens.get(pos) != Some(&Token::Slash) {
+        return Ok((left, pos));
+    }
+
+    let mut operands = vec!(left);
+    while tokens.get(pos) == Some(&Token::Slash) {
+        let (operand, next) = parse_power(tokens, pos + 1)?;
+        operands.push(operand);
+        pos = next;
+    }
+    Ok((ExpressionNode::Quotient { position: ParsePosition::default(), operands }, pos))
+}
+
+///
+/// ```text
+/// power ::= value{'^'value}
+/// ```
+///
+fn parse_power(tokens: &[Token], pos: usize) -> Result<(ExpressionNode, usize), ParsingError> {
+    let (base, pos) = parse_value(tokens, pos)?;
+    if tokens.get(pos) != Some(&Token::Caret) {
+        return Ok((base, pos));
+    }
+
+    let (exponent, pos) = parse_value(tokens, pos + 1)?;
+    Ok((ExpressionNode::Power { position: ParsePosition::default(), base: Box::new(base), exponent: Box::new(exponent) }, pos))
+}
+
+///
+/// ```text
+/// value ::= [parenthesis | number]
+/// parenthesis ::= {sign} '(' expression ')'
+/// ```
+///
+fn parse_value(tokens: &[Token], pos: usize) -> Result<(ExpressionNode, usize), ParsingError> {
+    match tokens.get(pos) {
+        Some(Token::LeftParen) => {
+            let (inner, pos) = parse_sum(tokens, pos + 1)?;
+            match tokens.get(pos) {
+                Some(Token::RightParen) => Ok((
+                    ExpressionNode::Parenthesis { position: ParsePosition::default(), sign: super::value::SignType::Positive, inner: Box::new(inner) },
+                    pos + 1
+                )),
+                _ => Err(ParsingError::EndOfInput(ParsePosition::default())),
+            }
+        },
+        Some(Token::Minus) => match tokens.get(pos + 1) {
+            Some(Token::LeftParen) => {
+                let (inner, pos) = parse_sum(tokens, pos + 2)?;
+                match tokens.get(pos) {
+                    Some(Token::RightParen) => Ok((
+                        ExpressionNode::Parenthesis { position: ParsePosition::default(), sign: super::value::SignType::Negative, inner: Box::new(inner) },
+                        pos + 1
+                    )),
+                    _ => Err(ParsingError::EndOfInput(ParsePosition::default())),
+                }
+            },
+            Some(Token::Integer(value)) => Ok((ExpressionNode::Integer { position: ParsePosition::default(), value: -value }, pos + 2)),
+            Some(Token::Decimal(value)) => Ok((ExpressionNode::Decimal { position: ParsePosition::default(), value: -value }, pos + 2)),
+            _ => Err(ParsingError::EndOfInput(ParsePosition::default())),
+        },
+        Some(Token::Integer(value)) => Ok((ExpressionNode::Integer { position: ParsePosition::default(), value: *value }, pos + 1)),
+        Some(Token::Decimal(value)) => Ok((ExpressionNode::Decimal { position: ParsePosition::default(), value: *value }, pos + 1)),
+        _ => Err(ParsingError::EndOfInput(ParsePosition::default())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::node::Evaluate;
+    use crate::expression::parse::{parse, ParseOptions};
+    use crate::scan::context::beginning;
+
+    #[test]
+    fn test_tokenize_simple_expression() {
+        let tokens = tokenize("1 + 2 * 3").unwrap();
+        assert_eq!(
+            vec!(Token::Integer(1), Token::Plus, Token::Integer(2), Token::Star, Token::Integer(3)),
+            tokens
+        );
+    }
+
+    #[test]
+    fn test_token_iter_yields_expected_sequence() {
+        let tokens: Result<Vec<Token>, ParsingError> = token_iter("1 + 2").collect();
+        assert_eq!(
+            vec!(Token::Integer(1), Token::Plus, Token::Integer(2)),
+            tokens.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_token_iter_stops_at_end_of_input() {
+        let mut iter = token_iter("1");
+        assert_eq!(Some(Ok(Token::Integer(1))), iter.next());
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn test_parse_tokens_matches_parse_on_string() {
+        let s = "1 + 2 * 3";
+        let tokens = tokenize(s).unwrap();
+        let token_node = parse_tokens(&tokens).unwrap();
+
+        let (_context, string_node) = parse(s, beginning(), &ParseOptions::default()).unwrap();
+
+        assert_eq!(string_node.evaluate(), token_node.evaluate());
+        assert_eq!(string_node.to_string(), token_node.to_string());
+    }
+}
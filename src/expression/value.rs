@@ -5,29 +5,233 @@
 //! operate on ExpressValue instances directly.
 //!
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+use super::error::EvaluationError;
 
 pub type DecimalType = f64;
 pub type IntegerType = i32;
 #[derive(Debug, Clone, PartialEq)]
 pub enum ExpressionValue {
-    NaN,
+    NaN {
+        // why this NaN came about, if known; `None` for a bare literal `NaN`
+        // or an unbound variable, where there's nothing to report.
+        reason: Option<EvaluationError>,
+    },
     Decimal {
         value: DecimalType,  // value of the number
     },
     Integer {
         value: IntegerType,  // value of the number
     },
+    /// an exact fraction that a `Decimal` can't represent without rounding
+    /// (e.g. integer `3 / 2`, or `2 ^ -3`), always kept in lowest terms with
+    /// a positive `denominator`: arithmetic builds one only through the
+    /// private [rational] constructor, and negating one only flips the sign
+    /// of its `numerator`; a `denominator` of `1` never appears here since
+    /// [rational] collapses that back to `Integer`
+    Rational {
+        numerator: i64,
+        denominator: i64,
+    },
+    /// a `Decimal` operation overflowed to an infinite `f64`; kept distinct
+    /// from `Decimal` so callers can tell it apart from a large finite value
+    Infinity {
+        sign: SignType,
+    },
 }
 impl Display for ExpressionValue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ExpressionValue::NaN => f.write_str("NaN"),
+            ExpressionValue::NaN { reason: _ } => f.write_str("NaN"),
             ExpressionValue::Decimal { value } => {
                 f.write_fmt(format_args!("{}", value))
             },
             ExpressionValue::Integer { value } => {
                 f.write_fmt(format_args!("{}", value))
             },
+            ExpressionValue::Rational { numerator, denominator } => {
+                f.write_fmt(format_args!("{}/{}", numerator, denominator))
+            },
+            ExpressionValue::Infinity { sign: SignType::Positive } => f.write_str("inf"),
+            ExpressionValue::Infinity { sign: SignType::Negative } => f.write_str("-inf"),
+        }
+    }
+}
+
+impl ExpressionValue {
+    ///
+    /// Format this value, trimming a `Decimal`'s unnecessary trailing zeros
+    /// and a dangling decimal point (`6.0` -> `"6"`, `1.50` -> `"1.5"`),
+    /// while always keeping at least one digit (`0.0` -> `"0"`).
+    ///
+    pub fn format_trimmed(&self) -> String {
+        match self {
+            ExpressionValue::NaN { reason: _ } | ExpressionValue::Integer { value: _ } | ExpressionValue::Infinity { sign: _ } | ExpressionValue::Rational { numerator: _, denominator: _ } => self.to_string(),
+            ExpressionValue::Decimal { value } => {
+                let mut text = format!("{}", value);
+                if text.contains('.') {
+                    while text.ends_with('0') {
+                        text.pop();
+                    }
+                    if text.ends_with('.') {
+                        text.pop();
+                    }
+                }
+                text
+            },
+        }
+    }
+}
+
+///
+/// `value` as a plain `f64`, for feeding into raw IEEE-754 arithmetic:
+/// `Infinity` becomes `f64::INFINITY`/`NEG_INFINITY` and `NaN` becomes
+/// `f64::NAN`, so the result of combining it with another value via normal
+/// float math already follows IEEE rules (e.g. `inf - inf` is `NaN`).
+///
+pub(crate) fn numeric_value(value: &ExpressionValue) -> DecimalType {
+    match value {
+        ExpressionValue::NaN { reason: _ } => DecimalType::NAN,
+        ExpressionValue::Decimal { value } => *value,
+        ExpressionValue::Integer { value } => *value as DecimalType,
+        ExpressionValue::Rational { numerator, denominator } => *numerator as DecimalType / *denominator as DecimalType,
+        ExpressionValue::Infinity { sign } => infinity_sign_to_f64(sign),
+    }
+}
+
+///
+/// The signed infinite `f64` corresponding to `sign`.
+///
+pub(crate) fn infinity_sign_to_f64(sign: &SignType) -> DecimalType {
+    match sign {
+        SignType::Positive => DecimalType::INFINITY,
+        SignType::Negative => DecimalType::NEG_INFINITY,
+    }
+}
+
+///
+/// The inverse of [numeric_value]: a raw `f64` arithmetic result, reclassified
+/// as `Infinity` if it overflowed, `NaN` (with no particular reason) if it's
+/// an indeterminate form like `inf - inf`, or `Decimal` otherwise.
+///
+pub(crate) fn value_from_f64(value: DecimalType) -> ExpressionValue {
+    if value.is_nan() {
+        ExpressionValue::NaN { reason: None }
+    } else if value.is_infinite() {
+        ExpressionValue::Infinity { sign: SignType::from(value.is_sign_positive()) }
+    } else {
+        ExpressionValue::Decimal { value }
+    }
+}
+
+///
+/// Build a [ExpressionValue::Rational] in lowest terms with a positive
+/// `denominator`, collapsing back to `Integer` when the denominator reduces
+/// to `1` (or `Decimal`, in the unlikely case the reduced numerator no
+/// longer fits `IntegerType`), so callers only ever see a bare `Rational`
+/// when the value genuinely isn't a whole number. A zero `denominator`
+/// produces the same `NaN{DivideByZero}` that dividing by zero does.
+///
+fn rational(numerator: i64, denominator: i64) -> ExpressionValue {
+    if denominator == 0 {
+        return ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) };
+    }
+    let (numerator, denominator) = if denominator < 0 { (-numerator, -denominator) } else { (numerator, denominator) };
+    let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs()).max(1) as i64;
+    let numerator = numerator / divisor;
+    let denominator = denominator / divisor;
+    if denominator == 1 {
+        if numerator >= IntegerType::MIN as i64 && numerator <= IntegerType::MAX as i64 {
+            ExpressionValue::Integer { value: numerator as IntegerType }
+        } else {
+            ExpressionValue::Decimal { value: numerator as DecimalType }
+        }
+    } else {
+        ExpressionValue::Rational { numerator, denominator }
+    }
+}
+
+///
+/// Greatest common divisor, for reducing a [rational] to lowest terms.
+///
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+///
+/// Post-evaluation pass that collapses a `Decimal` with no fractional part
+/// into the equivalent `Integer`, e.g. `Decimal{6.0}` becomes `Integer{6}`,
+/// so arithmetic that happens to land on a whole number presents as one.
+/// Leaves `NaN`, an already-`Integer` value, and any `Decimal` with a
+/// fractional part or outside `i32` range unchanged.
+///
+pub fn collapse_exact_decimals(value: ExpressionValue) -> ExpressionValue {
+    match value {
+        ExpressionValue::Decimal { value } if value.fract() == 0.0 && value >= IntegerType::MIN as DecimalType && value <= IntegerType::MAX as DecimalType => {
+            ExpressionValue::Integer { value: value as IntegerType }
+        },
+        other => other,
+    }
+}
+
+///
+/// Wraps an [ExpressionValue] so it can be used as a `HashMap`/`HashSet` key,
+/// which `ExpressionValue` itself can't be since `f64` (used by `Decimal`)
+/// is not `Eq`/`Hash`. Decimals are compared and hashed by their bit pattern
+/// (`f64::to_bits`), with any `NaN` bit pattern normalized to a single
+/// canonical one first, so any two `NaN` decimals are equal to, and hash
+/// the same as, each other. The `NaN` variant equals only another `NaN`
+/// variant, not a `NaN` decimal.
+///
+#[derive(Debug, Clone)]
+pub struct HashableValue(pub ExpressionValue);
+
+impl PartialEq for HashableValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (ExpressionValue::NaN { reason: _ }, ExpressionValue::NaN { reason: _ }) => true,
+            (ExpressionValue::Decimal { value: left }, ExpressionValue::Decimal { value: right }) => {
+                if left.is_nan() && right.is_nan() {
+                    true
+                } else {
+                    left.to_bits() == right.to_bits()
+                }
+            },
+            (ExpressionValue::Integer { value: left }, ExpressionValue::Integer { value: right }) => left == right,
+            (ExpressionValue::Rational { numerator: left_n, denominator: left_d }, ExpressionValue::Rational { numerator: right_n, denominator: right_d }) => left_n == right_n && left_d == right_d,
+            (ExpressionValue::Infinity { sign: left }, ExpressionValue::Infinity { sign: right }) => left == right,
+            _ => false,
+        }
+    }
+}
+impl Eq for HashableValue {}
+
+impl Hash for HashableValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match &self.0 {
+            ExpressionValue::NaN { reason: _ } => 0u8.hash(state),
+            ExpressionValue::Decimal { value } => {
+                1u8.hash(state);
+                if value.is_nan() {
+                    f64::NAN.to_bits().hash(state);
+                } else {
+                    value.to_bits().hash(state);
+                }
+            },
+            ExpressionValue::Integer { value } => {
+                2u8.hash(state);
+                value.hash(state);
+            },
+            ExpressionValue::Infinity { sign } => {
+                3u8.hash(state);
+                sign.hash(state);
+            },
+            ExpressionValue::Rational { numerator, denominator } => {
+                4u8.hash(state);
+                numerator.hash(state);
+                denominator.hash(state);
+            },
         }
     }
 }
@@ -39,8 +243,8 @@ pub trait Power<Rhs = Self> {
     ///
     /// # Example
     ///
-    /// ```
-    /// assert_eq!(12 ^ 2, 144);
+    /// ```text
+    /// 12.power(2) == 144
     /// ```
     #[must_use = "this returns the result of the operation, without modifying the original"]
     fn power(self, rhs: Rhs) -> Self::Output;
@@ -54,16 +258,53 @@ impl Power for ExpressionValue {
 
     fn power(self, rhs: Self) -> Self::Output {
         match self {
-            ExpressionValue::NaN => ExpressionValue::NaN,
+            ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+            ExpressionValue::Infinity { sign } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+                rhs => value_from_f64(infinity_sign_to_f64(&sign).powf(numeric_value(&rhs))),
+            },
             ExpressionValue::Decimal { value: left_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: left_value.powf(value) },
-                ExpressionValue::Integer { value } => ExpressionValue::Decimal{ value: left_value.powf(value as DecimalType) },
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+                ExpressionValue::Infinity { sign } => value_from_f64(left_value.powf(infinity_sign_to_f64(&sign))),
+                ExpressionValue::Decimal { value } => value_from_f64(left_value.powf(value)),
+                ExpressionValue::Integer { value } => value_from_f64(left_value.powf(value as DecimalType)),
+                ExpressionValue::Rational { numerator, denominator } => value_from_f64(left_value.powf(numerator as DecimalType / denominator as DecimalType)),
             },
             ExpressionValue::Integer { value: left_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: (left_value as DecimalType).powf(value) },
-                ExpressionValue::Integer { value } => ExpressionValue::Integer{ value: (left_value as DecimalType).powi(value) as IntegerType },
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+                ExpressionValue::Infinity { sign } => value_from_f64((left_value as DecimalType).powf(infinity_sign_to_f64(&sign))),
+                ExpressionValue::Decimal { value } => value_from_f64((left_value as DecimalType).powf(value)),
+                ExpressionValue::Rational { numerator, denominator } => value_from_f64((left_value as DecimalType).powf(numerator as DecimalType / denominator as DecimalType)),
+                // a negative integer exponent produces an exact fractional result
+                // (e.g. 2^-3 is 1/8), so build the reciprocal as a Rational instead
+                // of losing precision in a Decimal; a non-negative exponent always
+                // lands on a whole number, so it stays an Integer.
+                ExpressionValue::Integer { value } if value < 0 => match (left_value as i64).checked_pow(value.unsigned_abs()) {
+                    Some(denominator) => rational(1, denominator),
+                    None => ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) },
+                },
+                ExpressionValue::Integer { value } => match left_value.checked_pow(value as u32) {
+                    Some(result) => ExpressionValue::Integer { value: result },
+                    None => ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) },
+                },
+            },
+            ExpressionValue::Rational { numerator: left_numerator, denominator: left_denominator } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
+                ExpressionValue::Infinity { sign } => value_from_f64((left_numerator as DecimalType / left_denominator as DecimalType).powf(infinity_sign_to_f64(&sign))),
+                ExpressionValue::Decimal { value } => value_from_f64((left_numerator as DecimalType / left_denominator as DecimalType).powf(value)),
+                ExpressionValue::Rational { numerator, denominator } => value_from_f64((left_numerator as DecimalType / left_denominator as DecimalType).powf(numerator as DecimalType / denominator as DecimalType)),
+                ExpressionValue::Integer { value } => {
+                    let exponent = value.unsigned_abs();
+                    let checked = if value < 0 {
+                        left_denominator.checked_pow(exponent).zip(left_numerator.checked_pow(exponent))
+                    } else {
+                        left_numerator.checked_pow(exponent).zip(left_denominator.checked_pow(exponent))
+                    };
+                    match checked {
+                        Some((numerator, denominator)) => rational(numerator, denominator),
+                        None => ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) },
+                    }
+                },
             },
         }
     }
@@ -77,16 +318,34 @@ impl std::ops::Add for &ExpressionValue {
 
     fn add(self, rhs: Self) -> Self::Output {
         match self {
-            ExpressionValue::NaN => ExpressionValue::NaN,
+            ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+            ExpressionValue::Infinity { sign } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                rhs => value_from_f64(infinity_sign_to_f64(sign) + numeric_value(rhs)),
+            },
             ExpressionValue::Decimal { value: decimal_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: decimal_value + value },
-                ExpressionValue::Integer { value } => ExpressionValue::Decimal{ value: decimal_value + (*value as DecimalType)},
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64(decimal_value + infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64(decimal_value + value),
+                ExpressionValue::Integer { value } => value_from_f64(decimal_value + (*value as DecimalType)),
+                ExpressionValue::Rational { numerator, denominator } => value_from_f64(decimal_value + (*numerator as DecimalType / *denominator as DecimalType)),
             },
             ExpressionValue::Integer { value: integer_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: *integer_value as DecimalType + value },
-                ExpressionValue::Integer { value } => ExpressionValue::Integer{ value: integer_value + value},
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64(*integer_value as DecimalType + infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64(*integer_value as DecimalType + value),
+                ExpressionValue::Integer { value } => match integer_value.checked_add(*value) {
+                    Some(result) => ExpressionValue::Integer { value: result },
+                    None => ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) },
+                },
+                ExpressionValue::Rational { numerator, denominator } => rational(*integer_value as i64 * denominator + numerator, *denominator),
+            },
+            ExpressionValue::Rational { numerator: left_numerator, denominator: left_denominator } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64((*left_numerator as DecimalType / *left_denominator as DecimalType) + infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64((*left_numerator as DecimalType / *left_denominator as DecimalType) + value),
+                ExpressionValue::Integer { value } => rational(left_numerator + (*value as i64) * left_denominator, *left_denominator),
+                ExpressionValue::Rational { numerator, denominator } => rational(left_numerator * denominator + numerator * left_denominator, left_denominator * denominator),
             },
         }
     }
@@ -105,16 +364,34 @@ impl std::ops::Sub for &ExpressionValue {
 
     fn sub(self, rhs: Self) -> Self::Output {
         match self {
-            ExpressionValue::NaN => ExpressionValue::NaN,
+            ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+            ExpressionValue::Infinity { sign } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                rhs => value_from_f64(infinity_sign_to_f64(sign) - numeric_value(rhs)),
+            },
             ExpressionValue::Decimal { value: decimal_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: decimal_value - value },
-                ExpressionValue::Integer { value } => ExpressionValue::Decimal{ value: decimal_value - (*value as DecimalType)},
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64(decimal_value - infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64(decimal_value - value),
+                ExpressionValue::Integer { value } => value_from_f64(decimal_value - (*value as DecimalType)),
+                ExpressionValue::Rational { numerator, denominator } => value_from_f64(decimal_value - (*numerator as DecimalType / *denominator as DecimalType)),
             },
             ExpressionValue::Integer { value: integer_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: *integer_value as DecimalType - value },
-                ExpressionValue::Integer { value } => ExpressionValue::Integer{ value: integer_value - value},
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64(*integer_value as DecimalType - infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64(*integer_value as DecimalType - value),
+                ExpressionValue::Integer { value } => match integer_value.checked_sub(*value) {
+                    Some(result) => ExpressionValue::Integer { value: result },
+                    None => ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) },
+                },
+                ExpressionValue::Rational { numerator, denominator } => rational(*integer_value as i64 * denominator - numerator, *denominator),
+            },
+            ExpressionValue::Rational { numerator: left_numerator, denominator: left_denominator } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64((*left_numerator as DecimalType / *left_denominator as DecimalType) - infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64((*left_numerator as DecimalType / *left_denominator as DecimalType) - value),
+                ExpressionValue::Integer { value } => rational(left_numerator - (*value as i64) * left_denominator, *left_denominator),
+                ExpressionValue::Rational { numerator, denominator } => rational(left_numerator * denominator - numerator * left_denominator, left_denominator * denominator),
             },
         }
     }
@@ -133,16 +410,34 @@ impl std::ops::Mul for &ExpressionValue {
 
     fn mul(self, rhs: Self) -> Self::Output {
         match self {
-            ExpressionValue::NaN => ExpressionValue::NaN,
+            ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+            ExpressionValue::Infinity { sign } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                rhs => value_from_f64(infinity_sign_to_f64(sign) * numeric_value(rhs)),
+            },
             ExpressionValue::Decimal { value: decimal_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: decimal_value * value },
-                ExpressionValue::Integer { value } => ExpressionValue::Decimal{ value: decimal_value * (*value as DecimalType)},
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64(decimal_value * infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64(decimal_value * value),
+                ExpressionValue::Integer { value } => value_from_f64(decimal_value * (*value as DecimalType)),
+                ExpressionValue::Rational { numerator, denominator } => value_from_f64(decimal_value * (*numerator as DecimalType / *denominator as DecimalType)),
             },
             ExpressionValue::Integer { value: integer_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: *integer_value as DecimalType * value },
-                ExpressionValue::Integer { value } => ExpressionValue::Integer{ value: integer_value * value},
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64(*integer_value as DecimalType * infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64(*integer_value as DecimalType * value),
+                ExpressionValue::Integer { value } => match integer_value.checked_mul(*value) {
+                    Some(result) => ExpressionValue::Integer { value: result },
+                    None => ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) },
+                },
+                ExpressionValue::Rational { numerator, denominator } => rational(*integer_value as i64 * numerator, *denominator),
+            },
+            ExpressionValue::Rational { numerator: left_numerator, denominator: left_denominator } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64((*left_numerator as DecimalType / *left_denominator as DecimalType) * infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } => value_from_f64((*left_numerator as DecimalType / *left_denominator as DecimalType) * value),
+                ExpressionValue::Integer { value } => rational(left_numerator * (*value as i64), *left_denominator),
+                ExpressionValue::Rational { numerator, denominator } => rational(left_numerator * numerator, left_denominator * denominator),
             },
         }
     }
@@ -161,20 +456,40 @@ impl std::ops::Div for &ExpressionValue {
 
     fn div(self, rhs: Self) -> Self::Output {
         match self {
-            ExpressionValue::NaN => ExpressionValue::NaN,
+            ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+            ExpressionValue::Infinity { sign } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                rhs => value_from_f64(infinity_sign_to_f64(sign) / numeric_value(rhs)),
+            },
             ExpressionValue::Decimal { value: decimal_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } if *value == 0.0 => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: decimal_value / value },
-                ExpressionValue::Integer { value: 0 } => ExpressionValue::NaN,
-                ExpressionValue::Integer { value } => ExpressionValue::Decimal{ value: decimal_value / (*value as DecimalType)},
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64(decimal_value / infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } if *value == 0.0 => ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) },
+                ExpressionValue::Decimal { value } => value_from_f64(decimal_value / value),
+                ExpressionValue::Integer { value: 0 } => ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) },
+                ExpressionValue::Integer { value } => value_from_f64(decimal_value / (*value as DecimalType)),
+                ExpressionValue::Rational { numerator, denominator } => value_from_f64(decimal_value / (*numerator as DecimalType / *denominator as DecimalType)),
             },
             ExpressionValue::Integer { value: integer_value } => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } if *value == 0.0  => ExpressionValue::NaN,
-                ExpressionValue::Decimal { value } => ExpressionValue::Decimal{ value: *integer_value as DecimalType / value },
-                ExpressionValue::Integer { value: 0 } => ExpressionValue::NaN,
-                ExpressionValue::Integer { value } => ExpressionValue::Integer{ value: integer_value / value},
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64(*integer_value as DecimalType / infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } if *value == 0.0  => ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) },
+                ExpressionValue::Decimal { value } => value_from_f64(*integer_value as DecimalType / value),
+                ExpressionValue::Integer { value: 0 } => ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) },
+                // an exact integer quotient (e.g. 4 / 2) collapses back to Integer
+                // inside `rational`; one that isn't (e.g. 3 / 2) stays a Rational
+                // rather than truncating the way plain integer `/` would.
+                ExpressionValue::Integer { value } => rational(*integer_value as i64, *value as i64),
+                ExpressionValue::Rational { numerator, denominator } => rational(*integer_value as i64 * denominator, *numerator),
+            },
+            ExpressionValue::Rational { numerator: left_numerator, denominator: left_denominator } => match rhs {
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason: reason.clone() },
+                ExpressionValue::Infinity { sign } => value_from_f64((*left_numerator as DecimalType / *left_denominator as DecimalType) / infinity_sign_to_f64(sign)),
+                ExpressionValue::Decimal { value } if *value == 0.0 => ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) },
+                ExpressionValue::Decimal { value } => value_from_f64((*left_numerator as DecimalType / *left_denominator as DecimalType) / value),
+                ExpressionValue::Integer { value: 0 } => ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) },
+                ExpressionValue::Integer { value } => rational(*left_numerator, left_denominator * (*value as i64)),
+                ExpressionValue::Rational { numerator, denominator } => rational(left_numerator * denominator, left_denominator * numerator),
             },
         }
     }
@@ -194,9 +509,11 @@ impl std::ops::Mul<SignType> for ExpressionValue {
     fn mul(self, rhs: SignType) -> Self::Output {
         match rhs {
             SignType::Negative => match self {
-                ExpressionValue::NaN => ExpressionValue::NaN,
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
                 ExpressionValue::Decimal { value } => ExpressionValue::Decimal { value: -value },
                 ExpressionValue::Integer { value } => ExpressionValue::Integer { value: -value },
+                ExpressionValue::Rational { numerator, denominator } => ExpressionValue::Rational { numerator: -numerator, denominator },
+                ExpressionValue::Infinity { sign } => ExpressionValue::Infinity { sign: sign.negated() },
             },
             SignType::Positive => self,
         }
@@ -212,9 +529,11 @@ impl std::ops::Mul<ExpressionValue> for &SignType {
     fn mul(self, rhs: ExpressionValue) -> Self::Output {
         match self {
             SignType::Negative => match rhs {
-                ExpressionValue::NaN => ExpressionValue::NaN,
+                ExpressionValue::NaN { reason } => ExpressionValue::NaN { reason },
                 ExpressionValue::Decimal { value } => ExpressionValue::Decimal { value: -value },
                 ExpressionValue::Integer { value } => ExpressionValue::Integer { value: -value },
+                ExpressionValue::Rational { numerator, denominator } => ExpressionValue::Rational { numerator: -numerator, denominator },
+                ExpressionValue::Infinity { sign } => ExpressionValue::Infinity { sign: sign.negated() },
             },
             SignType::Positive => rhs,
         }
@@ -222,11 +541,20 @@ impl std::ops::Mul<ExpressionValue> for &SignType {
 }
 
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 pub enum SignType {
     Negative = -1,
     Positive = 1
 }
+impl SignType {
+    /// the opposite sign
+    pub fn negated(&self) -> SignType {
+        match self {
+            SignType::Negative => SignType::Positive,
+            SignType::Positive => SignType::Negative,
+        }
+    }
+}
 ///
 /// true -> SignType::Positive
 /// false -> SignType::Negative
@@ -275,4 +603,362 @@ impl From<SignType> for IntegerType {
     }
 }
 
+///
+/// The operator between a pair of operands in an [ExpressionNode::ComparisonChain](super::node::ExpressionNode::ComparisonChain).
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComparisonOp {
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl ComparisonOp {
+    ///
+    /// The infix operator text for this comparison, e.g. `"<"`.
+    ///
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ComparisonOp::LessThan => "<",
+            ComparisonOp::LessOrEqual => "<=",
+            ComparisonOp::GreaterThan => ">",
+            ComparisonOp::GreaterOrEqual => ">=",
+            ComparisonOp::Equal => "==",
+            ComparisonOp::NotEqual => "!=",
+        }
+    }
+
+    ///
+    /// Apply this comparison to a pair of already-evaluated operands.
+    /// Returns `false` when either side is [ExpressionValue::NaN], for every
+    /// operator including `!=` (where IEEE-754 `f64` comparison would give `true`).
+    ///
+    pub fn apply(&self, left: &ExpressionValue, right: &ExpressionValue) -> bool {
+        let (Some(left), Some(right)) = (as_comparable(left), as_comparable(right)) else { return false };
+        match self {
+            ComparisonOp::LessThan => left < right,
+            ComparisonOp::LessOrEqual => left <= right,
+            ComparisonOp::GreaterThan => left > right,
+            ComparisonOp::GreaterOrEqual => left >= right,
+            ComparisonOp::Equal => left == right,
+            ComparisonOp::NotEqual => left != right,
+        }
+    }
+}
+
+///
+/// How an [ExpressionValue::Integer] division rounds when the exact quotient
+/// isn't itself an integer, for [ExpressionValue::div_rounded]. The
+/// [std::ops::Div] impl above keeps the exact quotient instead of rounding
+/// it, returning an [ExpressionValue::Rational] rather than picking one of
+/// these; `div_rounded` is for callers that specifically want an `Integer`.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Rounding {
+    /// toward zero, e.g. `-7 / 2` is `-3`
+    #[default]
+    Truncate,
+    /// toward negative infinity, e.g. `-7 / 2` is `-4`
+    Floor,
+    /// toward positive infinity, e.g. `-7 / 2` is `-3`, `7 / 2` is `4`
+    Ceil,
+    /// to the nearest integer, ties away from zero, e.g. `-7 / 2` is `-4`
+    Round,
+}
+
+impl ExpressionValue {
+    ///
+    /// Like the `/` operator (see [std::ops::Div]), but for a pair of
+    /// [ExpressionValue::Integer] operands whose quotient isn't itself an
+    /// integer, rounds to an `Integer` according to `rounding` instead of
+    /// returning the exact `Rational` that `/` would. Any operand that isn't
+    /// a pair of integers (a `Decimal`, `Rational`, `NaN`, or `Infinity`)
+    /// divides exactly as `/` would, since `rounding` only matters when the
+    /// exact quotient is itself not an integer.
+    ///
+    pub fn div_rounded(&self, rhs: &ExpressionValue, rounding: Rounding) -> ExpressionValue {
+        match (self, rhs) {
+            (ExpressionValue::Integer { value: dividend }, ExpressionValue::Integer { value: divisor }) if *divisor != 0 => {
+                // `IntegerType::MIN / -1` overflows `IntegerType::MAX + 1`, the same
+                // edge case `checked_div`/`checked_rem` exist to catch.
+                let (Some(truncated), Some(remainder)) = (dividend.checked_div(*divisor), dividend.checked_rem(*divisor)) else {
+                    return ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) };
+                };
+                let value = match rounding {
+                    Rounding::Truncate => truncated,
+                    Rounding::Floor => if remainder != 0 && (remainder < 0) != (*divisor < 0) { truncated - 1 } else { truncated },
+                    Rounding::Ceil => if remainder != 0 && (remainder < 0) == (*divisor < 0) { truncated + 1 } else { truncated },
+                    Rounding::Round => {
+                        let doubled_remainder = remainder.abs() * 2;
+                        if doubled_remainder >= divisor.abs() {
+                            if (remainder < 0) == (*divisor < 0) { truncated + 1 } else { truncated - 1 }
+                        } else {
+                            truncated
+                        }
+                    },
+                };
+                ExpressionValue::Integer { value }
+            },
+            (left, right) => left / right,
+        }
+    }
+}
+
+///
+/// `value` as a `f64`, or `None` for [ExpressionValue::NaN].
+///
+fn as_comparable(value: &ExpressionValue) -> Option<DecimalType> {
+    match value {
+        ExpressionValue::NaN { reason: _ } => None,
+        ExpressionValue::Integer { value } => Some(*value as DecimalType),
+        ExpressionValue::Decimal { value } => Some(*value),
+        ExpressionValue::Rational { numerator, denominator } => Some(*numerator as DecimalType / *denominator as DecimalType),
+        ExpressionValue::Infinity { sign } => Some(infinity_sign_to_f64(sign)),
+    }
+}
+
 // TODO: port the parser test from https://github.com/Ezward/ExpressionCalculator/blob/master/test/com/lumpofcode/expression/ExpressionParserTest.java
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_trimmed_strips_trailing_zero() {
+        assert_eq!("6", ExpressionValue::Decimal { value: 6.0 }.format_trimmed());
+    }
+
+    #[test]
+    fn test_format_trimmed_keeps_significant_fraction() {
+        assert_eq!("1.5", ExpressionValue::Decimal { value: 1.50 }.format_trimmed());
+    }
+
+    #[test]
+    fn test_format_trimmed_zero_keeps_one_digit() {
+        assert_eq!("0", ExpressionValue::Decimal { value: 0.0 }.format_trimmed());
+    }
+
+    #[test]
+    fn test_format_trimmed_integer_and_nan_unaffected() {
+        assert_eq!("42", ExpressionValue::Integer { value: 42 }.format_trimmed());
+        assert_eq!("NaN", ExpressionValue::NaN { reason: None }.format_trimmed());
+    }
+
+    #[test]
+    fn test_collapse_exact_decimals_collapses_whole_number() {
+        assert_eq!(ExpressionValue::Integer { value: 6 }, collapse_exact_decimals(ExpressionValue::Decimal { value: 6.0 }));
+    }
+
+    #[test]
+    fn test_collapse_exact_decimals_leaves_fractional_value_unchanged() {
+        assert_eq!(ExpressionValue::Decimal { value: 6.5 }, collapse_exact_decimals(ExpressionValue::Decimal { value: 6.5 }));
+    }
+
+    #[test]
+    fn test_collapse_exact_decimals_leaves_out_of_range_value_unchanged() {
+        let huge = (IntegerType::MAX as DecimalType) * 2.0;
+        assert_eq!(ExpressionValue::Decimal { value: huge }, collapse_exact_decimals(ExpressionValue::Decimal { value: huge }));
+    }
+
+    #[test]
+    fn test_comparison_op_less_than_mixed_integer_and_decimal() {
+        assert!(ComparisonOp::LessThan.apply(&ExpressionValue::Integer { value: 1 }, &ExpressionValue::Decimal { value: 1.5 }));
+        assert!(!ComparisonOp::LessThan.apply(&ExpressionValue::Decimal { value: 1.5 }, &ExpressionValue::Integer { value: 1 }));
+    }
+
+    #[test]
+    fn test_comparison_op_nan_is_never_true() {
+        assert!(!ComparisonOp::LessThan.apply(&ExpressionValue::NaN { reason: None }, &ExpressionValue::Integer { value: 1 }));
+        assert!(!ComparisonOp::Equal.apply(&ExpressionValue::NaN { reason: None }, &ExpressionValue::NaN { reason: None }));
+    }
+
+    #[test]
+    fn test_div_by_zero_produces_nan_with_divide_by_zero_reason() {
+        let result = &ExpressionValue::Integer { value: 1 } / &ExpressionValue::Integer { value: 0 };
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) }, result);
+    }
+
+    #[test]
+    fn test_div_rounded_truncate_rounds_toward_zero() {
+        let left = ExpressionValue::Integer { value: -7 };
+        let right = ExpressionValue::Integer { value: 2 };
+        assert_eq!(ExpressionValue::Integer { value: -3 }, left.div_rounded(&right, Rounding::Truncate));
+        // unlike div_rounded, the `/` operator now keeps the exact quotient as a Rational
+        assert_eq!(ExpressionValue::Rational { numerator: -7, denominator: 2 }, &left / &right);
+    }
+
+    #[test]
+    fn test_div_rounded_floor_rounds_toward_negative_infinity() {
+        let left = ExpressionValue::Integer { value: -7 };
+        let right = ExpressionValue::Integer { value: 2 };
+        assert_eq!(ExpressionValue::Integer { value: -4 }, left.div_rounded(&right, Rounding::Floor));
+    }
+
+    #[test]
+    fn test_div_rounded_ceil_rounds_toward_positive_infinity() {
+        let left = ExpressionValue::Integer { value: 7 };
+        let right = ExpressionValue::Integer { value: 2 };
+        assert_eq!(ExpressionValue::Integer { value: 4 }, left.div_rounded(&right, Rounding::Ceil));
+    }
+
+    #[test]
+    fn test_div_rounded_round_ties_away_from_zero() {
+        let left = ExpressionValue::Integer { value: -5 };
+        let right = ExpressionValue::Integer { value: 2 };
+        assert_eq!(ExpressionValue::Integer { value: -3 }, left.div_rounded(&right, Rounding::Round));
+    }
+
+    #[test]
+    fn test_div_rounded_by_zero_produces_nan_regardless_of_rounding() {
+        let left = ExpressionValue::Integer { value: 1 };
+        let right = ExpressionValue::Integer { value: 0 };
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) }, left.div_rounded(&right, Rounding::Floor));
+    }
+
+    #[test]
+    fn test_div_rounded_min_by_negative_one_produces_nan_with_overflow_reason() {
+        let left = ExpressionValue::Integer { value: IntegerType::MIN };
+        let right = ExpressionValue::Integer { value: -1 };
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) }, left.div_rounded(&right, Rounding::Floor));
+    }
+
+    #[test]
+    fn test_add_sub_mul_preserve_left_most_nan_reason() {
+        let dividend_error = ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) };
+        let other = ExpressionValue::Integer { value: 2 };
+
+        assert_eq!(dividend_error, &dividend_error + &other);
+        assert_eq!(dividend_error, &other - &dividend_error);
+        assert_eq!(dividend_error, &dividend_error * &other);
+    }
+
+    #[test]
+    fn test_decimal_overflow_yields_infinity() {
+        let huge = ExpressionValue::Decimal { value: 1e200 };
+        assert_eq!(ExpressionValue::Infinity { sign: SignType::Positive }, &huge * &huge);
+    }
+
+    #[test]
+    fn test_infinity_minus_infinity_is_nan() {
+        let positive_infinity = ExpressionValue::Infinity { sign: SignType::Positive };
+        assert_eq!(ExpressionValue::NaN { reason: None }, &positive_infinity - &positive_infinity);
+    }
+
+    #[test]
+    fn test_infinity_display() {
+        assert_eq!("inf", ExpressionValue::Infinity { sign: SignType::Positive }.to_string());
+        assert_eq!("-inf", ExpressionValue::Infinity { sign: SignType::Negative }.to_string());
+    }
+
+    #[test]
+    fn test_infinity_plus_finite_stays_infinity() {
+        let positive_infinity = ExpressionValue::Infinity { sign: SignType::Positive };
+        let one = ExpressionValue::Integer { value: 1 };
+        assert_eq!(positive_infinity, &positive_infinity + &one);
+    }
+
+    fn hash_of(value: &HashableValue) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_hashable_value_equal_decimals_are_equal_and_hash_equal() {
+        let left = HashableValue(ExpressionValue::Decimal { value: 1.5 });
+        let right = HashableValue(ExpressionValue::Decimal { value: 1.5 });
+
+        assert_eq!(left, right);
+        assert_eq!(hash_of(&left), hash_of(&right));
+    }
+
+    #[test]
+    fn test_hashable_value_nan_wrappers_are_equal() {
+        let nan_variant = HashableValue(ExpressionValue::NaN { reason: None });
+        let nan_decimal = HashableValue(ExpressionValue::Decimal { value: f64::NAN });
+        let other_nan_decimal = HashableValue(ExpressionValue::Decimal { value: -f64::NAN });
+
+        assert_eq!(nan_decimal, other_nan_decimal);
+        assert_eq!(hash_of(&nan_decimal), hash_of(&other_nan_decimal));
+        assert_ne!(nan_variant, nan_decimal);
+    }
+
+    #[test]
+    fn test_hashable_value_usable_as_hashmap_key() {
+        let mut map = std::collections::HashMap::new();
+        map.insert(HashableValue(ExpressionValue::Decimal { value: 1.5 }), "one and a half");
+
+        assert_eq!(Some(&"one and a half"), map.get(&HashableValue(ExpressionValue::Decimal { value: 1.5 })));
+    }
+
+    #[test]
+    fn test_integer_division_not_evenly_divisible_produces_rational() {
+        let result = &ExpressionValue::Integer { value: 3 } / &ExpressionValue::Integer { value: 2 };
+        assert_eq!(ExpressionValue::Rational { numerator: 3, denominator: 2 }, result);
+        assert_eq!("3/2", result.to_string());
+    }
+
+    #[test]
+    fn test_negative_integer_power_produces_rational() {
+        let result = ExpressionValue::Integer { value: 2 }.power(ExpressionValue::Integer { value: -2 });
+        assert_eq!(ExpressionValue::Rational { numerator: 1, denominator: 4 }, result);
+    }
+
+    #[test]
+    fn test_integer_power_overflow_produces_nan_with_overflow_reason() {
+        let result = ExpressionValue::Integer { value: 2 }.power(ExpressionValue::Integer { value: 40 });
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) }, result);
+    }
+
+    #[test]
+    fn test_negative_integer_power_overflow_produces_nan_with_overflow_reason() {
+        let result = ExpressionValue::Integer { value: 3 }.power(ExpressionValue::Integer { value: -64 });
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) }, result);
+    }
+
+    #[test]
+    fn test_decimal_power_does_not_overflow() {
+        let result = ExpressionValue::Decimal { value: 2.0 }.power(ExpressionValue::Decimal { value: 40.0 });
+        assert_eq!(ExpressionValue::Decimal { value: 2f64.powf(40.0) }, result);
+    }
+
+    #[test]
+    fn test_integer_add_overflow_produces_nan_with_overflow_reason() {
+        let result = &ExpressionValue::Integer { value: IntegerType::MAX } + &ExpressionValue::Integer { value: 1 };
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) }, result);
+    }
+
+    #[test]
+    fn test_integer_sub_overflow_produces_nan_with_overflow_reason() {
+        let result = &ExpressionValue::Integer { value: IntegerType::MIN } - &ExpressionValue::Integer { value: 1 };
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) }, result);
+    }
+
+    #[test]
+    fn test_integer_mul_overflow_produces_nan_with_overflow_reason() {
+        let result = &ExpressionValue::Integer { value: IntegerType::MAX } * &ExpressionValue::Integer { value: 2 };
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::Overflow) }, result);
+    }
+
+    #[test]
+    fn test_rational_addition_normalizes_back_to_integer() {
+        let half = ExpressionValue::Rational { numerator: 1, denominator: 2 };
+        assert_eq!(ExpressionValue::Integer { value: 1 }, &half + &half);
+    }
+
+    #[test]
+    fn test_rational_is_kept_in_lowest_terms() {
+        let result = &ExpressionValue::Integer { value: 2 } / &ExpressionValue::Integer { value: 4 };
+        assert_eq!(ExpressionValue::Rational { numerator: 1, denominator: 2 }, result);
+    }
+
+    #[test]
+    fn test_rational_division_by_zero_produces_nan_with_divide_by_zero_reason() {
+        let half = ExpressionValue::Rational { numerator: 1, denominator: 2 };
+        let zero = ExpressionValue::Integer { value: 0 };
+        assert_eq!(ExpressionValue::NaN { reason: Some(EvaluationError::DivideByZero) }, &half / &zero);
+    }
+}
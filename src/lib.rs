@@ -0,0 +1,13 @@
+pub mod scan;
+pub mod expression;
+pub mod commute;
+// pub mod helpers;
+pub mod collection;
+pub mod util;
+
+///
+/// Re-exported for convenience, so callers don't need to reach into
+/// `scan::context` just to get a starting [ScanContext](scan::context::ScanContext)
+/// for [expression::parse::parse].
+///
+pub use scan::context::beginning;
@@ -1,19 +1,12 @@
 use std::process;
 
-use expression::{parse::parse, error::ParsingError};
-use scan::context::beginning;
-
-use crate::expression::node::{Evaluate, Position};
-
-pub mod scan;
-pub mod expression;
-// pub mod commute;
-// pub mod helpers;
-pub mod collection;
+use parser::beginning;
+use parser::expression::{parse::{parse, ParseOptions}, error::ParsingError};
+use parser::expression::node::{Evaluate, Position};
 
 fn main() -> Result<(), ParsingError> {
     if let Some(s) = std::env::args().nth(1) {
-        match parse(&s, beginning()) {
+        match parse(&s, beginning(), &ParseOptions::default()) {
             Ok((_position, expression)) => {
                 println!("{}", expression.evaluate());
                 Ok(())
@@ -101,6 +101,43 @@ pub fn beginning() -> ScanContext {
     (true, ScanPosition::default())
 }
 
+///
+/// The full `ScanPosition` (char index, line index, line starts) for byte
+/// offset `byte_index` into `s`, computed by scanning every character from
+/// the start. This is the slow, obviously-correct reference implementation
+/// that the incremental scanners (`scan_zero_or_more_chars` et al.) can be
+/// cross-checked against in tests; `byte_index` must land on a char boundary.
+///
+pub fn position_of(s: &str, byte_index: usize) -> ScanPosition {
+    let mut position = ScanPosition::default();
+    for ch in s[..byte_index].chars() {
+        if ch == NEWLINE {
+            position.line_index += 1;
+            position.line_byte_index = position.byte_index + NEWLINE_LEN;
+            position.line_char_index = position.char_index + 1;
+        }
+        position.byte_index += ch.len_utf8();
+        position.char_index += 1;
+    }
+    position
+}
+
+///
+/// In debug builds, assert that `ctx`'s scan position is internally
+/// consistent. Scanners already handle a `byte_index` beyond `s.len()`
+/// explicitly (by failing to match), so the real danger this guards
+/// against is a `byte_index` that lands in the middle of a multi-byte
+/// UTF-8 char: that would silently corrupt every subsequent scan, so
+/// catch it here rather than let it propagate.
+///
+#[cfg(debug_assertions)]
+fn debug_check(s: &str, ctx: &ScanContext) {
+    let (_matched, position) = ctx;
+    if position.byte_index <= s.len() {
+        assert!(s.is_char_boundary(position.byte_index), "ScanPosition byte_index {} does not land on a char boundary", position.byte_index);
+    }
+}
+
 ///
 /// Scan for a literal string.
 /// - **s**: the string to scan
@@ -125,6 +162,9 @@ pub fn scan_literal(
                             //      char offset after last matching char (aka number of utf-8 chars matched)
                             //      line offset after last matching char (aka number of line-endings scanned)
 {
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
     let (matched, mut position) = context;
     if (!matched) || position.byte_index > s.len(){
         return (false, position)
@@ -155,6 +195,66 @@ pub fn scan_literal(
     (true, position)
 }
 
+///
+/// Like [scan_literal], but compares characters with [char::eq_ignore_ascii_case],
+/// so `literal` matches input in any ASCII casing (e.g. `"TRUE"` matches `"true"`
+/// or `"True"`). Useful for case-insensitive keyword scanning (function names,
+/// constants) without having to call [scan_literal] once per casing.
+/// - **s**: the string to scan
+/// - **context**: the current scanning state
+/// - **literal**: the literal string to match, ignoring ASCII case
+/// - **returns**:
+///   - The scan result as a [ScanContext]
+///     - matched is true if entire literal matched, ignoring ASCII case
+///     - matched is false if any of literal did not match
+///     - byte offset is offset after last byte in last matching char (aka total number of bytes matched)
+///     - char offset is offset after last matching char (aka total number of utf-8 chars matched)
+///     - line offset is number of line endings scanned up to and including the last matched character.
+///
+pub fn scan_literal_ignore_case(
+    s: &str,                // IN : the string to scan
+    context: ScanContext,   // IN : scanning state
+    literal: &'static str)  // IN : the literal string to match, ignoring ASCII case
+    -> ScanContext          // RET: scan result as an ScanContext
+                            //      matched is false if not all chars in literal matched
+                            //      matched is true all chars in literal matched, ignoring ASCII case
+                            //      byte offset after last byte in last matching char (aka number of bytes matched)
+                            //      char offset after last matching char (aka number of utf-8 chars matched)
+                            //      line offset after last matching char (aka number of line-endings scanned)
+{
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
+    let (matched, mut position) = context;
+    if (!matched) || position.byte_index > s.len(){
+        return (false, position)
+    }
+
+    let mut _matches = 0;
+    let mut s_chars = s[position.byte_index..].chars();
+    for ch in literal.chars() {
+        if let Some(sch) = s_chars.next() {
+            if ch == NEWLINE {
+                position.line_index += 1;
+                position.line_byte_index = position.byte_index + NEWLINE_LEN;
+                position.line_char_index = position.char_index + 1;
+            }
+            if ch.eq_ignore_ascii_case(&sch) {
+                _matches += 1;
+                position.byte_index += sch.len_utf8();
+                position.char_index += 1;
+                continue;
+            }
+        }
+
+        // return context where match failed
+        return (false, position)
+    }
+
+    // entire literal matched
+    (true, position)
+}
+
 ///
 /// Greedy scan for any chars that pass test.
 /// - **s**: the string to scan
@@ -179,6 +279,9 @@ pub fn scan_zero_or_more_chars(
                             //      char offset after last matching char (aka total number of utf-8 chars matched)
                             //      line offset after last matching char (aka number of line-endings scanned)
 {
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
     let (matched, mut position) = context;
     if (!matched) || position.byte_index > s.len(){
         return (false, position)
@@ -227,6 +330,9 @@ pub fn scan_one_or_more_chars(
                             //      char offset after last matching char (aka number of utf-8 chars matched)
                             //      line offset after last matching char (aka number of line-endings scanned)
 {
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
     let (matched, mut position) = context;
     if (!matched) || position.byte_index > s.len(){
         return (false, position)
@@ -251,6 +357,45 @@ pub fn scan_one_or_more_chars(
     (matches > 0, position)
 }
 
+///
+/// Like [scan_one_or_more_chars], but also reports how many characters
+/// matched, for callers that need the count as well as the end position
+/// (e.g. requiring an even number of digits). `matched` is still only
+/// true if one or more characters matched; the count is 0 otherwise.
+///
+pub fn scan_chars_counted(
+    s: &str,                // IN : the string to scan
+    context: ScanContext,   // IN : the current scan state
+    test: fn(char) -> bool) // IN : the function that applies the test to the characters
+    -> (ScanContext, usize) // RET: scan result as an ScanContext, plus the number of matched characters
+{
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
+    let (matched, mut position) = context;
+    if (!matched) || position.byte_index > s.len(){
+        return ((false, position), 0)
+    }
+
+    let mut matches: usize = 0;
+    for ch in s[position.byte_index..].chars() {
+        if ! test(ch) {
+            return ((matches > 0, position), matches)
+        }
+        if ch == NEWLINE {
+            position.line_index += 1;
+            position.line_byte_index = position.byte_index + NEWLINE_LEN;
+            position.line_char_index = position.char_index + 1;
+        }
+        matches += 1;
+        position.byte_index += ch.len_utf8();
+        position.char_index += 1;
+    }
+
+    // entire string matches
+    ((matches > 0, position), matches)
+}
+
 ///
 /// Scan for exactly n characters that match the test.
 /// - **s**: the string to scan
@@ -277,6 +422,9 @@ pub fn scan_n_chars(
                             //      char offset after last matching char (aka number of utf-8 chars matched)
                             //      line offset after last matching char (aka number of line-endings scanned)
 {
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
     let (matched, mut position) = context;
     if (!matched) || position.byte_index > s.len(){
         return (false, position)
@@ -309,6 +457,233 @@ pub fn scan_n_chars(
     (n == matches, position)
 }
 
+///
+/// Scan exactly one character, matching if it is contained in `chars`.
+/// A cleaner primitive than writing a closure for an operator set like
+/// `scan_n_chars(s, context, 1, |ch| ch == '+' || ch == '-')`.
+/// - **s**: the string to scan
+/// - **context**: the current scanning state
+/// - **chars**: the set of characters to accept
+/// - **returns**:
+///   - The scan result as a [ScanContext]
+///     - matched is true if the next character is in `chars`
+///     - matched is false, with an unchanged position, if the next character
+///       is not in `chars`, or the context is already out of range
+///     - byte offset after the matched char's last byte (aka number of bytes matched)
+///     - char offset after the matched char (aka number of utf-8 chars matched)
+///     - line offset is incremented if the matched char was a newline
+///
+pub fn scan_one_of(
+    s: &str,                // IN : the string to scan
+    context: ScanContext,   // IN : the current scan state
+    chars: &[char])         // IN : the set of characters to accept
+    -> ScanContext          // RET: scan result as an ScanContext
+                            //      matched is false if the next char is not in chars
+                            //      matched is true if the next char is in chars
+                            //      byte offset after the matched char's last byte
+                            //      char offset after the matched char
+                            //      line offset incremented if the matched char was a newline
+{
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
+    let (matched, mut position) = context;
+    if (!matched) || position.byte_index > s.len(){
+        return (false, position)
+    }
+
+    match s[position.byte_index..].chars().next() {
+        Some(ch) if chars.contains(&ch) => {
+            if ch == NEWLINE {
+                position.line_index += 1;
+                position.line_byte_index = position.byte_index + NEWLINE_LEN;
+                position.line_char_index = position.char_index + 1;
+            }
+            position.byte_index += ch.len_utf8();
+            position.char_index += 1;
+            (true, position)
+        },
+        _ => (false, position),
+    }
+}
+
+///
+/// Scan every character up to (but not including) the next occurrence of
+/// `delimiter`, for things like comments or quoted strings that run until a
+/// terminator rather than matching a fixed character class.
+/// - **s**: the string to scan
+/// - **context**: the current scanning state
+/// - **delimiter**: the literal string to scan up to
+/// - **returns**:
+///   - The scan result as a [ScanContext]
+///     - matched is true, positioned right before `delimiter`, if `delimiter` was found
+///     - matched is false, positioned at end of input, if `delimiter` never appears
+///     - byte offset is offset of the first byte of `delimiter` (or of end of input)
+///     - char offset after last character scanned before `delimiter` (or end of input)
+///     - line offset is number of line endings scanned up to (but not including) `delimiter`
+///
+pub fn scan_until(
+    s: &str,                   // IN : the string to scan
+    context: ScanContext,      // IN : the current scan state
+    delimiter: &'static str)   // IN : the literal string to scan up to
+    -> ScanContext              // RET: scan result as an ScanContext
+                                //      matched is true, positioned before delimiter, if delimiter was found
+                                //      matched is false, positioned at end of input, if delimiter was never found
+{
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
+    let (matched, mut position) = context;
+    if (!matched) || position.byte_index > s.len(){
+        return (false, position)
+    }
+
+    if delimiter.is_empty() {
+        return (true, position)
+    }
+
+    loop {
+        if s[position.byte_index..].starts_with(delimiter) {
+            return (true, position)
+        }
+
+        let ch = match s[position.byte_index..].chars().next() {
+            Some(ch) => ch,
+            None => return (false, position), // end of input, delimiter never found
+        };
+
+        if ch == NEWLINE {
+            position.line_index += 1;
+            position.line_byte_index = position.byte_index + NEWLINE_LEN;
+            position.line_char_index = position.char_index + 1;
+        }
+        position.byte_index += ch.len_utf8();
+        position.char_index += 1;
+    }
+}
+
+///
+/// Slice the portion of `s` matched between `before` (the position scanning
+/// started at) and `after` (the [ScanContext] returned by a scanner),
+/// deduplicating the `s[before.byte_index..after.1.byte_index]` re-slicing
+/// that scanner callers (e.g. [parse_number](crate::expression::parse::parse_number))
+/// would otherwise repeat themselves.
+///
+/// Returns `None` if `after` didn't match, or if the byte range is invalid
+/// (e.g. `before` comes after `after`, or either falls outside `s` or off a
+/// char boundary).
+///
+pub fn scanned_str(s: &str, before: ScanPosition, after: ScanContext) -> Option<&str> {
+    let (matched, position) = after;
+    if !matched || before.byte_index > position.byte_index {
+        return None;
+    }
+    s.get(before.byte_index..position.byte_index)
+}
+
+///
+/// Look at the character at `context`'s current position without advancing
+/// past it, for disambiguating an operator that shares a prefix with a
+/// longer one (e.g. `*` vs `**`, `<` vs `<=`) before committing to a scan.
+///
+/// Returns `None` at end-of-input, or if `context` didn't match.
+///
+pub fn peek_char(s: &str, context: ScanContext) -> Option<char> {
+    let (matched, position) = context;
+    if !matched {
+        return None;
+    }
+    s[position.byte_index..].chars().next()
+}
+
+///
+/// Like [peek_char], but returns up to the next `n` characters (fewer if
+/// end-of-input is reached first) without advancing past them.
+///
+pub fn peek_str(s: &str, context: ScanContext, n: usize) -> Option<&str> {
+    let (matched, position) = context;
+    if !matched {
+        return None;
+    }
+    let mut end = position.byte_index;
+    for ch in s[position.byte_index..].chars().take(n) {
+        end += ch.len_utf8();
+    }
+    s.get(position.byte_index..end)
+}
+
+///
+/// Scan an optional sign ('-' or '+') followed by one or more digits.
+/// - **s**: the string to scan
+/// - **context**: the current scanning state
+/// - **returns**:
+///   - The scan result as a [ScanContext]
+///     - matched is true if one or more digits matched, whether or not a sign preceded them
+///     - matched is false if no digits matched (even if a sign alone was present)
+///     - byte offset after last byte in last matching char (aka number of bytes matched)
+///     - char offset after last matching char (aka number of utf-8 chars matched)
+///
+pub fn scan_signed_int(
+    s: &str,                // IN : the string to scan
+    context: ScanContext)   // IN : the current scan state
+    -> ScanContext          // RET: scan result as an ScanContext
+                            //      matched is false if zero digits matched
+                            //      matched is true if one or more digits matched
+{
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
+    let (matched, position) = context;
+    if !matched {
+        return (false, position);
+    }
+
+    let (has_minus, position) = scan_literal(s, (true, position), "-");
+    let position = if has_minus {
+        position
+    } else {
+        let (has_plus, plus_position) = scan_literal(s, (true, position), "+");
+        if has_plus { plus_position } else { position }
+    };
+
+    scan_one_or_more_chars(s, (true, position), |ch| ch.is_ascii_digit())
+}
+
+///
+/// Scan an identifier: an initial alphabetic character or `_`, followed by
+/// zero or more alphanumeric characters or `_`.
+/// - **s**: the string to scan
+/// - **context**: the current scanning state
+/// - **returns**:
+///   - The scan result as a [ScanContext]
+///     - matched is true if an identifier matched
+///     - matched is false if the first character is not alphabetic or `_`
+///     - byte offset after last byte in last matching char (aka number of bytes matched)
+///     - char offset after last matching char (aka number of utf-8 chars matched)
+///
+pub fn scan_identifier(
+    s: &str,                // IN : the string to scan
+    context: ScanContext)   // IN : the current scan state
+    -> ScanContext          // RET: scan result as an ScanContext
+                            //      matched is false if the first character is not alphabetic or '_'
+                            //      matched is true if an identifier matched
+{
+    #[cfg(debug_assertions)]
+    debug_check(s, &context);
+
+    let (matched, position) = context;
+    if !matched {
+        return (false, position);
+    }
+
+    let (has_first_char, first_char_position) = scan_n_chars(s, (true, position), 1, |ch| ch.is_alphabetic() || ch == '_');
+    if !has_first_char {
+        return (false, position);
+    }
+
+    scan_zero_or_more_chars(s, (true, first_char_position), |ch| ch.is_alphanumeric() || ch == '_')
+}
+
 #[cfg(test)]
 mod tests {
     use std::char;
@@ -361,6 +736,25 @@ mod tests {
 
     }
 
+    #[test]
+    fn test_scan_literal_ignore_case_ok_match() {
+        let s = "true";
+        let context = (true, ScanPosition::default());
+
+        let result = scan_literal_ignore_case(s, context, "TRUE");
+        assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_literal_ignore_case_ok_partial_mismatch() {
+        let s = "trUe";
+        let context = (true, ScanPosition::default());
+
+        // matches "tr" (ignoring case), then "U" mismatches "x"
+        let result = scan_literal_ignore_case(s, context, "trx");
+        assert_eq!((false, ScanPosition::new("tr".len(), 2, 0, 0, 0)), result);
+    }
+
     #[test]
     fn test_scan_literal_ok_out_of_range() {
         let s = "foo bar";
@@ -541,6 +935,113 @@ mod tests {
         assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), result);
     }
 
+    #[test]
+    fn test_scan_until_ok_delimiter_found() {
+        let s = "abc#rest";
+        let context = (true, ScanPosition::default());
+
+        let result = scan_until(s, context, "#");
+        assert_eq!((true, ScanPosition::new("abc".len(), 3, 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_until_ok_delimiter_never_found() {
+        let s = "abc rest";
+        let context = (true, ScanPosition::default());
+
+        let result = scan_until(s, context, "#");
+        assert_eq!((false, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_until_tracks_newlines_in_scanned_span() {
+        let s = "ab\ncd#rest";
+        let context = (true, ScanPosition::default());
+
+        let result = scan_until(s, context, "#");
+        assert_eq!((true, ScanPosition::new("ab\ncd".len(), 5, 1, 3, 3)), result);
+    }
+
+    #[test]
+    fn test_scan_until_ok_out_of_range() {
+        let s = "foo bar";
+
+        let context = (true, ScanPosition::new(s.len() + 69, s.chars().count() + 69, 0, 0, 0));
+        let result = scan_until(s, context, "#");
+        assert_eq!((false, context.1), result);
+    }
+
+    #[test]
+    fn test_scan_one_of_matches_char_in_set() {
+        let s = "+rest";
+        let context = (true, ScanPosition::default());
+
+        let result = scan_one_of(s, context, &['+', '-']);
+        assert_eq!((true, ScanPosition::new("+".len(), 1, 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_one_of_rejects_char_not_in_set() {
+        let s = "*rest";
+        let context = (true, ScanPosition::default());
+
+        let result = scan_one_of(s, context, &['+', '-']);
+        assert_eq!((false, ScanPosition::default()), result);
+    }
+
+    #[test]
+    fn test_scan_one_of_ok_out_of_range() {
+        let s = "foo bar";
+
+        let context = (true, ScanPosition::new(s.len() + 69, s.chars().count() + 69, 0, 0, 0));
+        let result = scan_one_of(s, context, &['+', '-']);
+        assert_eq!((false, context.1), result);
+    }
+
+    #[test]
+    fn test_scanned_str_ok_match() {
+        let s = "123 abc";
+        let before = ScanPosition::default();
+        let after = scan_one_or_more_chars(s, (true, before), |ch| ch.is_ascii_digit());
+
+        assert_eq!(Some("123"), scanned_str(s, before, after));
+    }
+
+    #[test]
+    fn test_scanned_str_none_on_failed_scan() {
+        let s = "abc";
+        let before = ScanPosition::default();
+        let after = scan_one_or_more_chars(s, (true, before), |ch| ch.is_ascii_digit());
+
+        assert_eq!(None, scanned_str(s, before, after));
+    }
+
+    #[test]
+    fn test_peek_char_returns_next_char_without_advancing() {
+        let s = "123";
+        let context = (true, ScanPosition::default());
+
+        assert_eq!(Some('1'), peek_char(s, context));
+        assert_eq!((true, ScanPosition::default()), context); // unchanged, nothing consumed
+    }
+
+    #[test]
+    fn test_peek_char_none_at_end_of_input() {
+        let s = "123";
+        let context = scan_n_chars(s, (true, ScanPosition::default()), 3, |ch| ch.is_ascii_digit());
+
+        assert_eq!(None, peek_char(s, context));
+    }
+
+    #[test]
+    fn test_peek_str_returns_next_n_chars_without_advancing() {
+        let s = "123";
+        let context = (true, ScanPosition::default());
+
+        assert_eq!(Some("12"), peek_str(s, context, 2));
+        assert_eq!((true, ScanPosition::default()), context); // unchanged, nothing consumed
+    }
+
 
     #[test]
     fn test_scan_chars_ok_sequentially() {
@@ -633,6 +1134,66 @@ mod tests {
         assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 2, "foo\nbar\r\n".len(), "foo\nbar\r\n".chars().count())), scan_n_chars(s, context, s.len(), |_ch| true));
     }
 
+    #[test]
+    #[should_panic]
+    fn test_debug_check_panics_on_non_char_boundary() {
+        let s = "βαρ"; // each char is 2 bytes, so byte index 1 is mid-char
+        let context = (true, ScanPosition::new(1, 0, 0, 0, 0));
+        scan_literal(s, context, "x");
+    }
+
+    #[test]
+    fn test_scan_signed_int_negative() {
+        let s = "-12";
+        let context = (true, ScanPosition::default());
+        assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), scan_signed_int(s, context));
+    }
+
+    #[test]
+    fn test_scan_signed_int_positive() {
+        let s = "+7";
+        let context = (true, ScanPosition::default());
+        assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), scan_signed_int(s, context));
+    }
+
+    #[test]
+    fn test_scan_signed_int_unsigned() {
+        let s = "5";
+        let context = (true, ScanPosition::default());
+        assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), scan_signed_int(s, context));
+    }
+
+    #[test]
+    fn test_scan_signed_int_no_match() {
+        let s = "-abc";
+        let context = (true, ScanPosition::default());
+
+        // a sign with no digits after it is not a match; position is left
+        // after the sign, since that's as far as scanning got.
+        assert_eq!((false, ScanPosition::new("-".len(), 1, 0, 0, 0)), scan_signed_int(s, context));
+    }
+
+    #[test]
+    fn test_scan_identifier_alphanumeric_with_underscore() {
+        let s = "foo_bar2";
+        let context = (true, ScanPosition::default());
+        assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), scan_identifier(s, context));
+    }
+
+    #[test]
+    fn test_scan_identifier_rejects_leading_digit() {
+        let s = "2foo";
+        let context = (true, ScanPosition::default());
+        assert_eq!((false, ScanPosition::default()), scan_identifier(s, context));
+    }
+
+    #[test]
+    fn test_scan_identifier_stops_at_operator() {
+        let s = "foo+bar";
+        let context = (true, ScanPosition::default());
+        assert_eq!((true, ScanPosition::new("foo".len(), 3, 0, 0, 0)), scan_identifier(s, context));
+    }
+
     #[test]
     fn test_scan_lines_last_line_ending_ok() {
         let s = "foo\nbar\r\nβαρ\r\n";
@@ -644,4 +1205,37 @@ mod tests {
         assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 3, s.len(), s.chars().count())), scan_n_chars(s, context, s.chars().count(), |_ch| true));
     }
 
+    #[test]
+    fn test_position_of_agrees_with_scan_zero_or_more_chars() {
+        let s = "foo\nbar\r\nβαρ\r\n";
+        let context = (true, ScanPosition::default());
+
+        for byte_index in [0, 1, 3, 4, "foo\n".len(), "foo\nbar\r\n".len(), s.len()] {
+            let (_matched, incremental_position) = scan_zero_or_more_chars(&s[..byte_index], context, |_ch| true);
+            assert_eq!(incremental_position, position_of(s, byte_index), "byte_index {}", byte_index);
+        }
+    }
+
+    #[test]
+    fn test_scan_chars_counted_reports_match_count() {
+        let s = "123x";
+        let context = (true, ScanPosition::default());
+
+        let ((matched, position), count) = scan_chars_counted(s, context, |ch| ch.is_ascii_digit());
+        assert!(matched);
+        assert_eq!(3, count);
+        assert_eq!(ScanPosition::new(3, 3, 0, 0, 0), position);
+    }
+
+    #[test]
+    fn test_scan_chars_counted_reports_zero_on_no_match() {
+        let s = "x123";
+        let context = (true, ScanPosition::default());
+
+        let ((matched, position), count) = scan_chars_counted(s, context, |ch| ch.is_ascii_digit());
+        assert!(!matched);
+        assert_eq!(0, count);
+        assert_eq!(ScanPosition::default(), position);
+    }
+
 }
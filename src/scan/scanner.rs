@@ -1,7 +1,7 @@
 //!
 //! Higher order scanners using the [Scanner] trait.
 //!
-use super::context::ScanContext;
+use super::context::{ScanContext, scan_one_or_more_chars, scan_zero_or_more_chars};
 
 ///
 /// A scanner function pointer that takes a string slice to
@@ -21,14 +21,36 @@ pub trait Scanner {
 }
 
 ///
-/// Implement [Scanner] trait for all [ScannerFn]
+/// Implement [Scanner] for any [ScannerFn] as well as any closure with the
+/// same signature, so a [Scanner] can be either a plain function (as before)
+/// or a closure built by a factory like [one_or_more]/[zero_or_more] that
+/// captures a `test` function.
 ///
-impl Scanner for fn(&str, ScanContext) -> ScanContext {
+impl<F> Scanner for F
+    where F: FnOnce(&str, ScanContext) -> ScanContext
+{
     fn scan(self, s: &str, context: ScanContext) -> ScanContext {
         self(s, context)
     }
 }
 
+///
+/// Build a [Scanner] that greedily matches one or more characters passing
+/// `test`, without writing a dedicated wrapper function (e.g. `scan_digits`)
+/// around [scan_one_or_more_chars] for each character class a grammar needs.
+///
+pub fn one_or_more(test: fn(char) -> bool) -> impl Scanner {
+    move |s: &str, context: ScanContext| scan_one_or_more_chars(s, context, test)
+}
+
+///
+/// Like [one_or_more], but matches zero or more characters, so the scanner
+/// still matches (consuming nothing) when none are present.
+///
+pub fn zero_or_more(test: fn(char) -> bool) -> impl Scanner {
+    move |s: &str, context: ScanContext| scan_zero_or_more_chars(s, context, test)
+}
+
 
 ///
 /// Scan for match by applying two scanners in order.
@@ -70,6 +92,95 @@ pub fn scan_pair(
 }
 
 
+///
+/// Scan for an optional sub-scanner, i.e. PEG's "zero or one" (`{}` in the grammar doc).
+/// Runs `scanner`; if it matches, its advanced context is returned as-is. If it doesn't,
+/// the original `context` is returned unchanged except that `matched` is forced to `true`,
+/// so an optional scanner never fails the surrounding sequence. This lets optional
+/// pieces of a grammar (a sign, a decimal point, an exponent) be expressed as
+/// combinators instead of hand-rolled `if`s.
+///
+/// - **s**: the string to scan
+/// - **context**: the current scanning state
+/// - **scanner**: the optional [Scanner] to try
+/// - **returns**:
+///   - The scan result as a [ScanContext]
+///     - matched is always true, unless context's byte offset is out of range
+///     - on success, the position is `scanner`'s result; on failure, `context`'s unchanged
+///
+#[allow(unused)]
+pub fn scan_optional(
+    s: &str,                   // IN : the string to scan
+    context: ScanContext,      // IN : scanning state
+    scanner: impl Scanner)     // IN : the optional scanner to try
+    -> ScanContext             // RET: scan result as an ScanContext
+                                //      matched is always true unless context is out of range
+                                //      on success, the position is scanner's result
+                                //      on failure, context's original position, matched forced true
+{
+    let (matched, position) = context;
+    if (!matched) || position.byte_index > s.len(){
+        return (false, position)
+    }
+
+    let attempt = scanner.scan(s, context);
+    if attempt.0 {
+        attempt
+    } else {
+        (true, position)
+    }
+}
+
+///
+/// Generalizes [crate::scan::context::scan_one_or_more_chars]/[crate::scan::context::scan_n_chars]
+/// from a single-character `test` to an arbitrary [Scanner], and from a fixed or unbounded
+/// repeat count to a `min..=max` range, e.g. "2 to 4 digits". Applies `scanner` repeatedly,
+/// counting successes, stopping once `max` (if given) is reached or `scanner` fails.
+///
+/// - **s**: the string to scan
+/// - **context**: the current scanning state
+/// - **min**: the minimum number of times `scanner` must match for `scan_repeat` to match
+/// - **max**: the maximum number of times to apply `scanner`, or `None` for unbounded
+/// - **scanner**: the [Scanner] to apply repeatedly
+/// - **returns**:
+///   - The scan result as a [ScanContext]
+///     - matched is true if `scanner` matched at least `min` times
+///     - matched is false if `scanner` matched fewer than `min` times, or context is out of range
+///     - the position reflects only the successfully consumed input: on success, the position
+///       after the last successful match; on failure, the position before the attempt that
+///       would have been the failing (`min`-th or earlier) match
+///
+#[allow(unused)]
+pub fn scan_repeat(
+    s: &str,                       // IN : the string to scan
+    context: ScanContext,          // IN : scanning state
+    min: usize,                    // IN : minimum number of matches required
+    max: Option<usize>,            // IN : maximum number of matches to attempt, or None for unbounded
+    scanner: impl Scanner + Copy)  // IN : the scanner to apply repeatedly
+    -> ScanContext                 // RET: scan result as an ScanContext
+                                    //      matched is true if scanner matched at least min times
+                                    //      matched is false if scanner matched fewer than min times
+                                    //      position reflects only successfully consumed input
+{
+    let (matched, position) = context;
+    if (!matched) || position.byte_index > s.len(){
+        return (false, position)
+    }
+
+    let mut count: usize = 0;
+    let mut scanned = context;
+    while max.is_none_or(|max| count < max) {
+        let attempt = scanner.scan(s, scanned);
+        if !attempt.0 {
+            break;
+        }
+        scanned = attempt;
+        count += 1;
+    }
+
+    (count >= min, scanned.1)
+}
+
 ///
 /// Scan for match by applying a sequence of scanners in order.
 /// Scanning proceeds in the order the iterator provides that scanners
@@ -158,14 +269,166 @@ fn scan_any<T>(
     scanned
 }
 
+///
+/// Scan for match by trying `scanners` in order, resetting to the original `context`
+/// before each attempt, so a scanner that consumes input before ultimately failing
+/// can't corrupt the starting position seen by the next alternative. This is true
+/// PEG ordered choice: `scan_any` threads whatever the previous failing scanner left
+/// behind into the next one, which (since every `scan_*` function's first guard is
+/// "if not matched, return immediately") means only the very first scanner ever gets
+/// a real chance to match; `scan_first` tries every alternative from the same start.
+///
+/// - **s**: the string to scan
+/// - **context**: the current scanning state
+/// - **scanners**: the [ScannerFn]s to try, in order, each from `context`
+/// - **returns**:
+///   - The scan result as a [ScanContext]
+///     - matched is true if any scanner matched, starting from `context`
+///     - matched is false if no scanner matched, or if context's byte offset is out of range
+///     - on success, the position is whatever the first matching scanner produced
+///     - on failure, the original `context`'s position is returned unchanged
+///
+#[allow(unused)]
+pub fn scan_first(
+    s: &str,                   // IN : the string to scan
+    context: ScanContext,      // IN : scanning state
+    scanners: &[ScannerFn])    // IN : the scanners to try, in order, each from `context`
+    -> ScanContext             // RET: scan result as an ScanContext
+                                //      matched is true if any scanner matched from `context`
+                                //      matched is false if no scanner matched
+                                //      on success, the position is the matching scanner's result
+                                //      on failure, context's original position is returned
+{
+    let (matched, position) = context;
+    if (!matched) || position.byte_index > s.len(){
+        return (false, position)
+    }
+
+    for scanner in scanners {
+        let attempt = scanner(s, context);
+        if attempt.0 {
+            return attempt;
+        }
+    }
+
+    (false, position)
+}
+
 
 #[cfg(test)]
 mod tests {
-    use crate::scan::context::{scan_one_or_more_chars, scan_zero_or_more_chars, scan_n_chars, ScanPosition};
+    use crate::scan::context::{scan_one_or_more_chars, scan_zero_or_more_chars, scan_n_chars, scan_literal, ScanPosition};
 
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
 
+    #[test]
+    fn test_one_or_more_factory_scans_digits() {
+        let s = "123abc";
+        let context = (true, ScanPosition::default());
+
+        let result = one_or_more(|ch| ch.is_ascii_digit()).scan(s, context);
+        assert_eq!((true, ScanPosition::new("123".len(), 3, 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_zero_or_more_factory_matches_when_none_present() {
+        let s = "abc";
+        let context = (true, ScanPosition::default());
+
+        let result = zero_or_more(|ch| ch.is_ascii_digit()).scan(s, context);
+        assert_eq!((true, ScanPosition::default()), result);
+    }
+
+    #[test]
+    fn test_digit_scanner_built_via_factory_runs_through_scan_all() {
+        let s = "123abc";
+        let context = (true, ScanPosition::default());
+
+        let scan_digits: ScannerFn = |s, c| one_or_more(|ch| ch.is_ascii_digit()).scan(s, c);
+        let scan_letters: ScannerFn = |s, c| zero_or_more(|ch| ch.is_alphabetic()).scan(s, c);
+        let result = scan_all(s, context, [scan_digits, scan_letters]);
+        assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_first_tries_the_next_alternative_from_the_original_context_after_a_partial_failure() {
+        let s = "foo";
+        let context = (true, ScanPosition::default());
+
+        // "fob" matches the leading "fo" before failing on "b" vs "o", which would
+        // corrupt the position seen by the next alternative if it were threaded through
+        let scan_fob: ScannerFn = |s, c| scan_literal(s, c, "fob");
+        let scan_foo: ScannerFn = |s, c| scan_literal(s, c, "foo");
+        let result = scan_first(s, context, &[scan_fob, scan_foo]);
+
+        assert_eq!((true, ScanPosition::new(s.len(), s.chars().count(), 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_first_fails_with_the_original_context_when_no_alternative_matches() {
+        let s = "foo";
+        let context = (true, ScanPosition::default());
+
+        let scan_fob: ScannerFn = |s, c| scan_literal(s, c, "fob");
+        let scan_bar: ScannerFn = |s, c| scan_literal(s, c, "bar");
+        let result = scan_first(s, context, &[scan_fob, scan_bar]);
+
+        assert_eq!((false, ScanPosition::default()), result);
+    }
+
+    #[test]
+    fn test_scan_optional_matches_when_inner_scanner_matches() {
+        let s = "123abc";
+        let context = (true, ScanPosition::default());
+
+        let scan_digits: ScannerFn = |s, c| one_or_more(|ch| ch.is_ascii_digit()).scan(s, c);
+        let result = scan_optional(s, context, scan_digits);
+        assert_eq!((true, ScanPosition::new("123".len(), 3, 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_optional_still_matches_when_inner_scanner_fails() {
+        let s = "abc";
+        let context = (true, ScanPosition::default());
+
+        let scan_digits: ScannerFn = |s, c| one_or_more(|ch| ch.is_ascii_digit()).scan(s, c);
+        let result = scan_optional(s, context, scan_digits);
+        assert_eq!((true, ScanPosition::default()), result);
+    }
+
+    #[test]
+    fn test_scan_repeat_fails_below_min() {
+        let s = "1abc";
+        let context = (true, ScanPosition::default());
+
+        let scan_digit: ScannerFn = |s, c| scan_n_chars(s, c, 1, |ch| ch.is_ascii_digit());
+        let result = scan_repeat(s, context, 2, Some(3), scan_digit);
+        // one digit matched before the second attempt failed on 'a'; the failing
+        // attempt's position is not reflected, only the successful first match is
+        assert_eq!((false, ScanPosition::new("1".len(), 1, 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_repeat_matches_within_min_and_max() {
+        let s = "12abc";
+        let context = (true, ScanPosition::default());
+
+        let scan_digit: ScannerFn = |s, c| scan_n_chars(s, c, 1, |ch| ch.is_ascii_digit());
+        let result = scan_repeat(s, context, 2, Some(3), scan_digit);
+        assert_eq!((true, ScanPosition::new("12".len(), 2, 0, 0, 0)), result);
+    }
+
+    #[test]
+    fn test_scan_repeat_stops_at_max_even_with_more_available() {
+        let s = "12345abc";
+        let context = (true, ScanPosition::default());
+
+        let scan_digit: ScannerFn = |s, c| scan_n_chars(s, c, 1, |ch| ch.is_ascii_digit());
+        let result = scan_repeat(s, context, 2, Some(3), scan_digit);
+        assert_eq!((true, ScanPosition::new("123".len(), 3, 0, 0, 0)), result);
+    }
+
     #[test]
     fn test_scan_pair_ok() {
         let s = "foo123bar_doo_2";
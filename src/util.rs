@@ -0,0 +1,38 @@
+//!
+//! Small, standalone helpers that don't belong to any one module.
+//!
+
+///
+/// Trim `s` and collapse every run of internal ASCII whitespace down to a
+/// single space, e.g. `"  1  +   2 "` becomes `"1 + 2"`. Useful before
+/// parsing to simplify downstream position reasoning. Non-whitespace
+/// characters are left untouched.
+///
+pub fn normalize_whitespace(s: &str) -> String {
+    s.split_ascii_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_whitespace_collapses_and_trims() {
+        assert_eq!("1 + 2", normalize_whitespace("  1  +   2 "));
+    }
+
+    #[test]
+    fn test_normalize_whitespace_leaves_single_spaced_text_unchanged() {
+        assert_eq!("1 + 2", normalize_whitespace("1 + 2"));
+    }
+
+    #[test]
+    fn test_normalize_whitespace_empty_string_stays_empty() {
+        assert_eq!("", normalize_whitespace(""));
+    }
+
+    #[test]
+    fn test_normalize_whitespace_all_whitespace_becomes_empty() {
+        assert_eq!("", normalize_whitespace("   \t  "));
+    }
+}